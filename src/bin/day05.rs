@@ -17,14 +17,33 @@ use std::cmp::Ordering;
 
     How many ingredient IDs are considered to be fresh according to the fresh ingredient ID ranges?
 
+    Explain mode (`cargo run --bin day05 -- explain`):
+
+    For every available ingredient, list the database range lines that make it fresh,
+    or the nearest ranges on either side when it is spoiled.
 
 */
+use aoc2025::sections::{SectionError, parse_sections};
 use aoc2025::time_it;
+use std::fmt;
+
+/// Merged fresh ranges and the available ingredient IDs.
+type Database = (Vec<(u64, u64)>, Vec<u64>);
 
 fn main() {
     let input = include_str!("../inputs/day05.txt");
 
-    let parsed = parse_ranges_and_numbers_fixed(input);
+    if std::env::args().nth(1).as_deref() == Some("explain") {
+        let (ranges, ingredients) = parse_database(input).unwrap_or_else(|err| panic!("{err}"));
+        let tree = IntervalTree::new(ranges);
+
+        for ingredient in ingredients {
+            println!("{}", tree.explain(ingredient));
+        }
+        return;
+    }
+
+    let parsed = parse_ranges_and_numbers_fixed(input).unwrap_or_else(|err| panic!("{err}"));
 
     let res_one = time_it!("part one: ", get_fresh_ingredients(&parsed)); // approx runtime: 220 us
     let res_two = time_it!("part two: ", get_all_fresh(parsed.0)); // approx run 2 us
@@ -47,7 +66,7 @@ fn get_all_fresh(ranges: Vec<(u64, u64)>) -> u128 {
 ///
 /// Expects `ranges` to be sorted, non-overlapping, and inclusive so a binary
 /// search can quickly determine membership for each ingredient.
-fn get_fresh_ingredients((ranges, ingredients): &(Vec<(u64, u64)>, Vec<u64>)) -> i32 {
+fn get_fresh_ingredients((ranges, ingredients): &Database) -> i32 {
     let mut count = 0;
 
     for ingredient in ingredients {
@@ -70,6 +89,162 @@ fn get_fresh_ingredients((ranges, ingredients): &(Vec<(u64, u64)>, Vec<u64>)) ->
     count
 }
 
+/// An inclusive range exactly as it appears in the database, with its 1-based line number.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct SourceRange {
+    line: usize,
+    start: u64,
+    end: u64,
+}
+
+impl SourceRange {
+    fn contains(&self, id: u64) -> bool {
+        self.start <= id && id <= self.end
+    }
+}
+
+impl fmt::Display for SourceRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}-{}", self.line, self.start, self.end)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Freshness {
+    /// Every original range containing the ingredient, in input order.
+    Fresh(Vec<SourceRange>),
+    /// The closest range ending before and starting after the ingredient.
+    Spoiled {
+        below: Option<SourceRange>,
+        above: Option<SourceRange>,
+    },
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct Explanation {
+    ingredient: u64,
+    freshness: Freshness,
+}
+
+impl fmt::Display for Explanation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.freshness {
+            Freshness::Fresh(ranges) => {
+                write!(f, "{} is fresh, covered by", self.ingredient)?;
+                for (idx, range) in ranges.iter().enumerate() {
+                    let sep = if idx == 0 { " " } else { ", " };
+                    write!(f, "{sep}[{range}]")?;
+                }
+                Ok(())
+            }
+            Freshness::Spoiled { below, above } => {
+                write!(f, "{} is spoiled", self.ingredient)?;
+                if let Some(below) = below {
+                    write!(f, ", nearest below [{below}]")?;
+                }
+                if let Some(above) = above {
+                    write!(f, ", nearest above [{above}]")?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Static interval tree over the unmerged database ranges.
+///
+/// Ranges are sorted by start and viewed as an implicit balanced BST (the middle
+/// of every slice is its root); `max_end[i]` is the largest end in the subtree
+/// rooted at `i`, which lets stabbing queries skip whole subtrees.
+struct IntervalTree {
+    by_start: Vec<SourceRange>,
+    max_end: Vec<u64>,
+    by_end: Vec<SourceRange>,
+}
+
+impl IntervalTree {
+    fn new(mut ranges: Vec<SourceRange>) -> Self {
+        ranges.sort_by_key(|r| (r.start, r.end, r.line));
+
+        let mut max_end = vec![0; ranges.len()];
+        Self::build(&ranges, &mut max_end, 0, ranges.len());
+
+        let mut by_end = ranges.clone();
+        by_end.sort_by_key(|r| (r.end, r.start, r.line));
+
+        IntervalTree {
+            by_start: ranges,
+            max_end,
+            by_end,
+        }
+    }
+
+    fn build(ranges: &[SourceRange], max_end: &mut [u64], lo: usize, hi: usize) -> u64 {
+        if lo >= hi {
+            return 0;
+        }
+
+        let mid = lo + (hi - lo) / 2;
+        let left = Self::build(ranges, max_end, lo, mid);
+        let right = Self::build(ranges, max_end, mid + 1, hi);
+
+        max_end[mid] = ranges[mid].end.max(left).max(right);
+        max_end[mid]
+    }
+
+    /// All ranges containing `id`, ordered by their line in the input.
+    fn stab(&self, id: u64) -> Vec<SourceRange> {
+        let mut found = Vec::new();
+        self.stab_into(id, 0, self.by_start.len(), &mut found);
+
+        found.sort_by_key(|r| r.line);
+        found
+    }
+
+    fn stab_into(&self, id: u64, lo: usize, hi: usize, found: &mut Vec<SourceRange>) {
+        if lo >= hi {
+            return;
+        }
+
+        let mid = lo + (hi - lo) / 2;
+        if self.max_end[mid] < id {
+            return; // nothing in this subtree reaches `id`
+        }
+
+        self.stab_into(id, lo, mid, found);
+
+        let range = self.by_start[mid];
+        if range.start <= id {
+            if range.contains(id) {
+                found.push(range);
+            }
+            self.stab_into(id, mid + 1, hi, found);
+        }
+    }
+
+    fn explain(&self, ingredient: u64) -> Explanation {
+        let covering = self.stab(ingredient);
+
+        let freshness = if covering.is_empty() {
+            // Largest end before the ingredient, smallest start after it.
+            let below_idx = self.by_end.partition_point(|r| r.end < ingredient);
+            let above_idx = self.by_start.partition_point(|r| r.start <= ingredient);
+
+            Freshness::Spoiled {
+                below: below_idx.checked_sub(1).map(|idx| self.by_end[idx]),
+                above: self.by_start.get(above_idx).copied(),
+            }
+        } else {
+            Freshness::Fresh(covering)
+        };
+
+        Explanation {
+            ingredient,
+            freshness,
+        }
+    }
+}
+
 /// Merge overlapping AND touching inclusive ranges.
 ///
 /// Input: arbitrary (possibly unsorted, overlapping) ranges (start, end)
@@ -115,34 +290,42 @@ fn merge_ranges_touching(mut ranges: Vec<(u64, u64)>) -> Vec<(u64, u64)> {
     merged
 }
 
-fn parse_ranges_and_numbers_fixed(input: &str) -> (Vec<(u64, u64)>, Vec<u64>) {
-    let mut parts = input.split("\n\n");
-
-    let ranges_block = parts.next().expect("missing ranges block");
-    let nums_block = parts.next().expect("missing numbers block");
-
-    let ranges = ranges_block
-        .lines()
-        .filter(|l| !l.trim().is_empty())
-        .map(|line| {
-            let (a, b) = line.split_once('-').expect("range must contain '-'");
-            (
-                a.trim().parse::<u64>().unwrap(),
-                b.trim().parse::<u64>().unwrap(),
-            )
-        })
-        .collect::<Vec<_>>();
+fn parse_ranges_and_numbers_fixed(input: &str) -> Result<Database, SectionError> {
+    let (ranges, numbers) = parse_database(input)?;
 
+    let ranges = ranges.into_iter().map(|r| (r.start, r.end)).collect();
     let mut ranges = merge_ranges_touching(ranges);
     ranges.sort_by_key(|&(start, _)| start);
 
-    let numbers = nums_block
-        .lines()
-        .filter(|l| !l.trim().is_empty())
-        .map(|line| line.trim().parse::<u64>().unwrap())
-        .collect::<Vec<_>>();
+    Ok((ranges, numbers))
+}
+
+/// Parses the database keeping every range as written, tagged with its line number.
+fn parse_database(input: &str) -> Result<(Vec<SourceRange>, Vec<u64>), SectionError> {
+    let sections = parse_sections(input, &["ranges", "ingredients"])?;
+
+    let mut ranges = Vec::new();
+    for (line_no, line) in sections[0].lines() {
+        let (a, b) = line.split_once('-').ok_or_else(|| {
+            sections[0].error(line_no, format!("{line:?}: range must contain '-'"))
+        })?;
+
+        let bound = |s: &str| {
+            s.trim()
+                .parse::<u64>()
+                .map_err(|err| sections[0].error(line_no, format!("{line:?}: {err}")))
+        };
+
+        ranges.push(SourceRange {
+            line: line_no,
+            start: bound(a)?,
+            end: bound(b)?,
+        });
+    }
+
+    let numbers = sections[1].parse_lines(str::parse::<u64>)?;
 
-    (ranges, numbers)
+    Ok((ranges, numbers))
 }
 
 #[cfg(test)]
@@ -162,7 +345,7 @@ mod tests {
 11
 17
 32"#;
-        let parsed = parse_ranges_and_numbers_fixed(test_input);
+        let parsed = parse_ranges_and_numbers_fixed(test_input).unwrap();
 
         assert_eq!(get_fresh_ingredients(&parsed), 3);
     }
@@ -180,8 +363,73 @@ mod tests {
 11
 17
 32"#;
-        let parsed = parse_ranges_and_numbers_fixed(test_input);
+        let parsed = parse_ranges_and_numbers_fixed(test_input).unwrap();
 
         assert_eq!(get_all_fresh(parsed.0), 14);
     }
+
+    #[test]
+    fn test_parse_crlf_and_extra_blank_lines() {
+        let test_input =
+            "3-5\r\n10-14\r\n16-20\r\n12-18\r\n\r\n\r\n1\r\n5\r\n8\r\n11\r\n17\r\n32\r\n\r\n";
+        let parsed = parse_ranges_and_numbers_fixed(test_input).unwrap();
+
+        assert_eq!(get_fresh_ingredients(&parsed), 3);
+
+        let err = parse_ranges_and_numbers_fixed("3-5\n10_14\n\n1\n").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "section `ranges`, line 2: \"10_14\": range must contain '-'"
+        );
+    }
+
+    #[test]
+    fn test_explain_example_input() {
+        let test_input = r#"3-5
+10-14
+16-20
+12-18
+
+1
+5
+8
+11
+17
+32"#;
+        let (ranges, ingredients) = parse_database(test_input).unwrap();
+        let tree = IntervalTree::new(ranges);
+
+        let explanations = ingredients
+            .into_iter()
+            .map(|id| tree.explain(id))
+            .collect::<Vec<_>>();
+
+        let range = |line, start, end| SourceRange { line, start, end };
+        assert_eq!(
+            explanations[0].freshness,
+            Freshness::Spoiled {
+                below: None,
+                above: Some(range(1, 3, 5))
+            }
+        );
+        assert_eq!(
+            explanations[1].freshness,
+            Freshness::Fresh(vec![range(1, 3, 5)])
+        );
+        assert_eq!(
+            explanations[2].freshness,
+            Freshness::Spoiled {
+                below: Some(range(1, 3, 5)),
+                above: Some(range(2, 10, 14))
+            }
+        );
+        assert_eq!(
+            explanations[4].freshness,
+            Freshness::Fresh(vec![range(3, 16, 20), range(4, 12, 18)])
+        );
+        assert_eq!(
+            explanations[5].to_string(),
+            "32 is spoiled, nearest below [line 3: 16-20]"
+        );
+    }
 }
@@ -1,167 +1,44 @@
-/*
-    day06:
-
-    I need to help cephalopods do their math homework
-
-    The puzzle input consists of a list of problems.
-    Each problem has a group of numbers that need to be either added (+) or multiplied (*)
-    Problems are arranged vertically and the last line is the symbol for the operation.
-
-    Part 1:
-
-    What is the grand total found by adding together all of the answers of the individual problems?
-
-    Part 2:
-
-    The final calculation remains the same, however the numbers are read differently.
-    every column is a number with its most sgnificat digit at the top. e.g.
-
-    12
-    157
-    1
-
-    is read as 7, 25, 111
-*/
+use aoc2025::day06::*;
 use aoc2025::time_it;
 
 fn main() {
-    let input = include_str!("../inputs/day06.txt");
-
-    let parsed_one = parse_columns_and_ops(input);
-    let parsed_two = parse_vertical_columns_and_ops(input);
-
-    let res_one = time_it!("part one: ", do_calculations(parsed_one));
-    let res_two = time_it!("part two: ", do_calculations(parsed_two));
-
-    println!("The grand total found for part one: {}", res_one);
-    println!("The grand total found for part two: {}", res_two);
-}
-
-fn do_calculations((columns, ops): (Vec<Vec<i64>>, Vec<char>)) -> u128 {
-    columns
-        .into_iter()
-        .zip(ops)
-        .map(|(col, op)| {
-            let values = col.into_iter().map(|x| x as u128);
-            if op == '*' {
-                values.into_iter().product::<u128>()
-            } else {
-                values.into_iter().sum::<u128>()
-            }
-        })
-        .sum()
-}
-
-fn parse_columns_and_ops(input: &str) -> (Vec<Vec<i64>>, Vec<char>) {
-    let mut lines: Vec<&str> = input
-        .lines()
-        .map(|l| l.trim())
-        .filter(|l| !l.is_empty())
-        .collect();
-
-    // The last line contains operators
-    let ops_line = lines.pop().unwrap();
-
-    // Parse operators (every non-space character)
-    let ops: Vec<char> = ops_line.chars().filter(|c| !c.is_whitespace()).collect();
-
-    // Parse number rows
-    let number_rows: Vec<Vec<i64>> = lines
-        .into_iter()
-        .map(|line| {
-            line.split_whitespace()
-                .map(|token| token.parse::<i64>().unwrap())
-                .collect()
-        })
-        .collect();
-
-    // Transpose rows → columns
-    let cols_count = number_rows[0].len();
-    let mut columns = vec![Vec::new(); cols_count];
-
-    for row in number_rows {
-        for (col_idx, value) in row.into_iter().enumerate() {
-            columns[col_idx].push(value);
-        }
+    let args: Vec<String> = std::env::args().collect();
+    if args.get(1).map(String::as_str) == Some("generate") {
+        let count = args.get(2).and_then(|n| n.parse().ok()).unwrap_or(1000);
+        let seed = args.get(3).and_then(|n| n.parse().ok()).unwrap_or(2025);
+
+        print!(
+            "{}",
+            render_worksheet(&generate_problems(count, seed), Reading::Rows)
+        );
+        return;
     }
 
-    (columns, ops)
-}
-fn parse_vertical_columns_and_ops(input: &str) -> (Vec<Vec<i64>>, Vec<char>) {
-    // 1) Collect non-empty lines, trimming only the right side.
-    let mut lines: Vec<String> = input
-        .lines()
-        .map(|l| l.trim_end().to_string())
-        .filter(|l| !l.trim().is_empty())
-        .collect();
-
-    assert!(!lines.is_empty(), "input must not be empty");
-
-    // 2) Last line: contains the operators.
-    let ops_line = lines.pop().unwrap();
-    let mut ops = Vec::new();
-    let mut op_positions = Vec::new();
-
-    // We treat every non-space char in the last line as an operator,
-    // and its byte index as the start of a block.
-    for (idx, b) in ops_line.bytes().enumerate() {
-        if !b.is_ascii_whitespace() {
-            ops.push(b as char);
-            op_positions.push(idx);
-        }
-    }
-
-    assert!(!ops.is_empty(), "no operators found");
-
-    // 3) Pad all number lines to the same width (so indexing is safe).
-    let width = ops_line
-        .len()
-        .max(lines.iter().map(|l| l.len()).max().unwrap_or(0));
-    let number_rows: Vec<Vec<u8>> = lines
-        .into_iter()
-        .map(|mut l| {
-            if l.len() < width {
-                l.push_str(&" ".repeat(width - l.len()));
-            }
-            l.into_bytes()
-        })
-        .collect();
-
-    let row_count = number_rows.len();
-
-    // 4) Compute block [start, end) ranges from operator positions.
-    let starts = op_positions;
-    let mut ends = starts.iter().skip(1).copied().collect::<Vec<usize>>();
-    ends.push(width); // last block ends at end of line
-
-    // 5) For each block, build its vertical numbers.
-    let mut all_columns: Vec<Vec<i64>> = Vec::with_capacity(starts.len());
-
-    for (&start, &end) in starts.iter().zip(ends.iter()) {
-        let mut numbers_in_block = Vec::new();
-
-        // For each character column inside this horizontal block:
-        for col in start..end {
-            let mut num_str = String::new();
-
-            // Read digits top→bottom in this character column.
-            for row in 0..row_count {
-                let b = number_rows[row][col];
-                if b.is_ascii_digit() {
-                    num_str.push(b as char);
-                }
-            }
-
-            if !num_str.is_empty() {
-                let value: i64 = num_str.parse().unwrap();
-                numbers_in_block.push(value);
-            }
-        }
-
-        all_columns.push(numbers_in_block);
-    }
-
-    (all_columns, ops)
+    // `cargo run --bin day06 -- bottom-to-top` reads part two's columns the other way up
+    let reading = match args.get(1).map(String::as_str) {
+        None | Some("top-to-bottom") => Reading::TopToBottom,
+        Some("bottom-to-top") => Reading::BottomToTop,
+        Some("right-to-left") => Reading::RightToLeft,
+        Some("rows") => Reading::Rows,
+        Some(other) => panic!(
+            "unknown reading {other:?}, expected top-to-bottom, bottom-to-top, right-to-left or rows"
+        ),
+    };
+
+    let layout = Layout::parse(INPUT).unwrap_or_else(|err| panic!("{err}"));
+    let parsed_one = layout
+        .problems(Reading::Rows)
+        .unwrap_or_else(|err| panic!("{err}"));
+    let parsed_two = layout
+        .problems(reading)
+        .unwrap_or_else(|err| panic!("{err}"));
+
+    let worksheet = Worksheet::default();
+    let res_one = time_it!("part one: ", worksheet.grand_total(&parsed_one));
+    let res_two = time_it!("part two: ", worksheet.grand_total(&parsed_two));
+
+    println!("The grand total found for part one: {}", res_one.unwrap());
+    println!("The grand total found for part two: {}", res_two.unwrap());
 }
 
 #[cfg(test)]
@@ -175,9 +52,12 @@ mod tests {
   6 98  215 314
 *   +   *   +
 "#;
-        let parsed = parse_columns_and_ops(test_input);
+        let parsed = Layout::parse(test_input)
+            .unwrap()
+            .problems(Reading::Rows)
+            .unwrap();
 
-        assert_eq!(do_calculations(parsed), 4277556)
+        assert_eq!(Worksheet::default().grand_total(&parsed), Ok(4277556))
     }
 
     #[test]
@@ -187,8 +67,177 @@ mod tests {
   6 98  215 314
 *   +   *   +
 "#;
-        let parsed = parse_vertical_columns_and_ops(test_input);
-        println!("{parsed:?}");
-        assert_eq!(do_calculations(parsed), 3263827)
+        let parsed = Layout::parse(test_input)
+            .unwrap()
+            .problems(Reading::TopToBottom)
+            .unwrap();
+
+        assert_eq!(Worksheet::default().grand_total(&parsed), Ok(3263827))
+    }
+
+    #[test]
+    fn test_worksheet_operators() {
+        let test_input = r#"20 7 9   12
+ 5 2 40   3
+ 1 3  6   5
+-  / min max
+"#;
+        let parsed = Layout::parse(test_input)
+            .unwrap()
+            .problems(Reading::Rows)
+            .unwrap();
+        let worksheet = Worksheet::default();
+
+        assert_eq!(
+            worksheet.evaluate(&parsed),
+            vec![Ok(14), Ok(1), Ok(6), Ok(12)]
+        );
+
+        let custom =
+            Worksheet::default().with_operator("^", |a, b| a.checked_pow(b.try_into().ok()?));
+        let parsed = Layout::parse("2  3\n10 4\n^  ?\n")
+            .unwrap()
+            .problems(Reading::Rows)
+            .unwrap();
+        assert_eq!(
+            custom.grand_total(&parsed),
+            Err(EvalError::UnknownOperator {
+                problem: 1,
+                symbol: "?".to_string()
+            })
+        );
+        assert_eq!(custom.evaluate(&parsed)[0], Ok(1024));
+
+        // operators may capture their environment
+        let modulus = 7;
+        let modular = Worksheet::default().with_operator("*%", move |a, b| {
+            a.checked_mul(b)?.checked_rem_euclid(modulus)
+        });
+        let parsed = Layout::parse("5  10\n6  11\n*% *%\n")
+            .unwrap()
+            .problems(Reading::Rows)
+            .unwrap();
+        assert_eq!(modular.evaluate(&parsed), vec![Ok(2), Ok(5)]);
+
+        let overflow = vec![Problem {
+            numbers: vec![i64::MAX, i64::MAX, i64::MAX],
+            operator: "*".to_string(),
+        }];
+        assert!(matches!(
+            worksheet.grand_total(&overflow),
+            Err(EvalError::Undefined { problem: 0, .. })
+        ));
+    }
+
+    #[test]
+    fn test_render_example_round_trips() {
+        let problems = vec![
+            Problem {
+                numbers: vec![123, 45, 6],
+                operator: "*".to_string(),
+            },
+            Problem {
+                numbers: vec![328, 64, 98],
+                operator: "+".to_string(),
+            },
+        ];
+
+        assert_eq!(
+            render_worksheet(&problems, Reading::Rows),
+            "123 328\n 45  64\n  6  98\n*   +\n"
+        );
+        assert_eq!(
+            render_worksheet(&problems, Reading::TopToBottom),
+            "146 369\n25  248\n3   8\n*   +\n"
+        );
+    }
+
+    #[test]
+    fn test_render_round_trips_for_every_reading() {
+        for seed in 0..200 {
+            let problems = generate_problems(1 + seed as usize % 6, seed);
+
+            for reading in [
+                Reading::Rows,
+                Reading::TopToBottom,
+                Reading::BottomToTop,
+                Reading::RightToLeft,
+            ] {
+                let text = render_worksheet(&problems, reading);
+                assert_eq!(
+                    Layout::parse(&text).unwrap().problems(reading).unwrap(),
+                    problems,
+                    "{reading:?}\n{text}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_layout_readings() {
+        let test_input = r#"123 328  51 64 
+ 45 64  387 23 
+  6 98  215 314
+*   +   *   +
+"#;
+        let layout = Layout::parse(test_input).unwrap();
+        let numbers = |reading| {
+            layout
+                .problems(reading)
+                .unwrap()
+                .into_iter()
+                .map(|p| p.numbers)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(numbers(Reading::Rows)[0], vec![123, 45, 6]);
+        assert_eq!(numbers(Reading::TopToBottom)[0], vec![1, 24, 356]);
+        assert_eq!(numbers(Reading::BottomToTop)[0], vec![1, 42, 653]);
+        assert_eq!(numbers(Reading::RightToLeft)[3], vec![4, 431, 623]);
+        assert_eq!(
+            layout
+                .blocks()
+                .iter()
+                .map(|b| (b.start, b.end))
+                .collect::<Vec<_>>(),
+            vec![(0, 3), (4, 7), (8, 11), (12, 15)]
+        );
+    }
+
+    #[test]
+    fn test_layout_errors() {
+        // the space splits "1 2" read as a row, but not the columns 13, 4 and 25
+        let layout = Layout::parse("1 2\n345\n+\n").unwrap();
+        assert_eq!(
+            layout.problems(Reading::Rows),
+            Err(LayoutError::SplitNumber {
+                problem: 0,
+                digits: "1 2".to_string()
+            })
+        );
+        assert_eq!(
+            layout.problems(Reading::TopToBottom).unwrap()[0].numbers,
+            vec![13, 4, 25]
+        );
+
+        assert_eq!(
+            Layout::parse("12 34\n+\n").unwrap_err(),
+            LayoutError::MissingOperator { start: 3, end: 5 }
+        );
+        assert_eq!(
+            Layout::parse("1234\n+ *\n").unwrap_err(),
+            LayoutError::MoreThanOneOperator { start: 0, end: 4 }
+        );
+        assert_eq!(
+            Layout::parse("12 34\n56 78\n").unwrap_err(),
+            LayoutError::NoOperators
+        );
+        assert_eq!(Layout::parse("\n").unwrap_err(), LayoutError::NoOperators);
+
+        let layout = Layout::parse("99999999999999999999\n+\n").unwrap();
+        assert!(matches!(
+            layout.problems(Reading::Rows),
+            Err(LayoutError::InvalidNumber { problem: 0, .. })
+        ));
     }
 }
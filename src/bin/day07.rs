@@ -1,181 +1,311 @@
-/*
-    day07:
-
-    Our tachyon minifolds have an issue. The diagram of these is our puzzle input.
-    The beam enters the minifold at the location marked 'S', beams always move downward.
-    The beams passe freely through empty spaces '.', However, if they found a splitter (^) the beam is stopped and it splits immediately.
-    They go left and right of the splitter.
-    The process continues until all the tachyoun beams reach a splitter or exit the manifold
-
-    Part one:
-
-    Howe many times will the beam be split?
-
-    Part two:
-
-    After all, it was not a normal tachyon manifold, it was a quantum tachyon manifold! This time it's only a particle that its flowing.
-    The particle could go right or left, each path creating a new timeline.
-
-    How many different timelines would a single tachyon particle end up on?
-
-*/
+use aoc2025::day07::*;
 use aoc2025::time_it;
-use std::collections::{HashMap, HashSet};
 
 fn main() {
-    let input = include_str!("../inputs/day07.txt");
+    let parsed = parse_manifold(INPUT);
 
-    let parsed = parse_manifold(input);
+    let edges = match std::env::args().nth(1).as_deref() {
+        None | Some("lost") => EdgePolicy::Lost,
+        Some("reflect") => EdgePolicy::Reflect,
+        Some("wrap") => EdgePolicy::Wrap,
+        Some(other) => panic!("unknown edge policy {other:?}, expected lost, reflect or wrap"),
+    };
 
-    let res_one = time_it!("part one: ", count_splits(&parsed));
-    let res_two = time_it!("part two: ", count_timelines(&parsed));
+    let (res_one, beams) = time_it!("part one: ", count_splits(&parsed, edges));
+    let res_two = time_it!("part two: ", count_timelines::<u64>(&parsed, edges));
+    let res_two = res_two.unwrap_or_else(|err| panic!("{err}"));
+    let total = res_two.total().unwrap_or_else(|err| panic!("{err}"));
 
     println!("The beam will be split {} times", res_one);
-    println!("The particle would generate {} timelines", res_two);
+    println!("The particle would generate {} timelines", total);
+    println!("Beams leaving the manifold: {beams:?}");
+    println!("Timelines leaving the manifold: {res_two:?}");
+
+    // `cargo run --bin day07 -- lost pgm > heatmap.pgm`
+    if let Some(format) = std::env::args().nth(2) {
+        let heatmap = timeline_heatmap::<u64>(&parsed, edges).unwrap_or_else(|err| panic!("{err}"));
+        match format.as_str() {
+            "ascii" => print!("{}", heatmap.render_ascii()),
+            "pgm" => print!("{}", heatmap.render_pgm()),
+            other => panic!("unknown heatmap format {other:?}, expected ascii or pgm"),
+        }
+    }
 }
 
-fn count_timelines(
-    (start, carets, rows): &(Option<(usize, usize)>, Vec<(usize, usize)>, usize),
-) -> usize {
-    let mut beams = HashMap::new();
-    beams.insert(start.unwrap(), 1);
-
-    let mut hits = 0;
-
-    while !beams.is_empty() {
-        let mut next_beams = HashMap::new();
-
-        for (beam_pos, count) in beams {
-            let next_row = beam_pos.0 + 1;
-            if next_row >= *rows {
-                continue;
-            }
-
-            if carets.contains(&(next_row, beam_pos.1)) {
-                hits += count;
-
-                // spawn two beams, count
-                *next_beams.entry((next_row, beam_pos.1 + 1)).or_insert(0) += count;
-                *next_beams.entry((next_row, beam_pos.1 - 1)).or_insert(0) += count;
-            } else {
-                // just go straight
-                *next_beams.entry((next_row, beam_pos.1)).or_insert(0) += count;
-            }
-        }
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-        beams = next_beams;
+    #[test]
+    fn test_part_one_example_input() {
+        let parsed = parse_manifold(TEST_INPUT);
+        assert_eq!(count_splits(&parsed, EdgePolicy::Lost).0, 21);
     }
 
-    hits + 1
-}
+    #[test]
+    fn test_part_two_example_input() {
+        let parsed = parse_manifold(TEST_INPUT);
+        assert_eq!(
+            count_timelines::<u64>(&parsed, EdgePolicy::Lost)
+                .unwrap()
+                .total(),
+            Ok(40)
+        );
+    }
 
-fn count_splits(
-    (start, carets, rows): &(Option<(usize, usize)>, Vec<(usize, usize)>, usize),
-) -> usize {
-    let mut beams = HashSet::from([start.unwrap()]);
-    let mut hitted_carets = HashSet::new();
+    #[test]
+    fn test_splitters_on_the_border() {
+        let test_input = r#"
+S..
+^.^
+.^.
+...
+"#;
 
-    while !beams.is_empty() {
-        let current_beams = beams.clone();
-        let mut next_beams = HashSet::new();
+        let parsed = parse_manifold(test_input);
 
-        for beam in &current_beams {
-            // if the beam is in the last row or left, skip it
-            if beam.0 + 1 >= *rows {
-                continue;
+        assert_eq!(
+            count_timelines::<usize>(&parsed, EdgePolicy::Lost).unwrap(),
+            Exits {
+                left: 1,
+                bottom: 2,
+                ..Exits::default()
             }
-
-            // if next beam position is a caret, split it. otherwise just advance
-            if carets.contains(&(beam.0 + 1, beam.1)) {
-                next_beams.insert((beam.0 + 1, beam.1 + 1)); // to the right
-                next_beams.insert((beam.0 + 1, beam.1 - 1)); // to the left
-                hitted_carets.insert((beam.0 + 1, beam.1));
-            } else {
-                next_beams.insert((beam.0 + 1, beam.1)); // just advance down
+        );
+        assert_eq!(
+            count_timelines::<usize>(&parsed, EdgePolicy::Reflect).unwrap(),
+            Exits {
+                bottom: 3,
+                ..Exits::default()
             }
-        }
+        );
+        assert_eq!(
+            count_splits(&parsed, EdgePolicy::Wrap),
+            (
+                2,
+                Exits {
+                    bottom: 2,
+                    ..Exits::default()
+                }
+            )
+        );
+    }
 
-        beams = next_beams;
+    #[test]
+    fn test_tracer_matches_sweep() {
+        // An unreachable absorber forces the general tracer on the example.
+        let traced = TEST_INPUT.replacen('.', "#", 1);
+        let parsed = parse_manifold(&traced);
+
+        for edges in [EdgePolicy::Lost, EdgePolicy::Reflect, EdgePolicy::Wrap] {
+            let swept = parse_manifold(TEST_INPUT);
+            assert_eq!(count_splits(&parsed, edges), count_splits(&swept, edges));
+            assert_eq!(
+                count_timelines::<usize>(&parsed, edges),
+                count_timelines::<usize>(&swept, edges)
+            );
+            assert_eq!(
+                timeline_heatmap::<usize>(&parsed, edges),
+                timeline_heatmap::<usize>(&swept, edges)
+            );
+        }
     }
 
-    hitted_carets.len()
-}
+    #[test]
+    fn test_timeline_heatmap() {
+        let test_input = r#"
+.S.
+...
+.^.
+...
+"#;
+        let heatmap =
+            timeline_heatmap::<usize>(&parse_manifold(test_input), EdgePolicy::Lost).unwrap();
+
+        assert_eq!(heatmap.render_ascii(), " @ \n @ \n@@@\n@ @\n");
+        assert_eq!(
+            heatmap.render_pgm(),
+            "P2\n3 4\n255\n0 255 0\n0 255 0\n255 255 255\n255 0 255\n"
+        );
+        assert_eq!(*heatmap.splitter_hits(2, 1), 1);
+
+        let heatmap =
+            timeline_heatmap::<usize>(&parse_manifold(TEST_INPUT), EdgePolicy::Lost).unwrap();
+        let last_row = (0..15)
+            .map(|col| *heatmap.timelines(15, col))
+            .sum::<usize>();
+        assert_eq!(last_row, 40);
+        assert_eq!(*heatmap.splitter_hits(2, 7), 1);
+        assert_eq!(*heatmap.splitter_hits(6, 7), 2);
+    }
 
-fn parse_manifold(input: &str) -> (Option<(usize, usize)>, Vec<(usize, usize)>, usize) {
-    let mut start: Option<(usize, usize)> = None;
-    let mut carets: Vec<(usize, usize)> = Vec::new();
+    #[test]
+    fn test_mirrors_splitters_and_absorbers() {
+        let test_input = r#"
+S...S
+.....
+\.-./
+..#..
+.....
+"#;
+        // left source: mirror sends it right into `-`, which passes it on to `/` and up out
+        // of the top; right source: `/` sends it left into `-`, passes on to `\` and up.
+        let parsed = parse_manifold(test_input);
+        assert_eq!(
+            count_timelines::<usize>(&parsed, EdgePolicy::Lost).unwrap(),
+            Exits {
+                top: 2,
+                ..Exits::default()
+            }
+        );
 
-    let mut rows = 0;
-    for (row, line) in input.lines().enumerate() {
-        // Skip empty lines that appear because of leading newline in raw string
-        if line.trim().is_empty() {
-            continue;
-        }
+        let test_input = r#"
+.S.
+...
+.|.
+.-.
+"#;
+        // `|` along the beam passes it; `-` across it splits it off both sides
+        let parsed = parse_manifold(test_input);
+        assert_eq!(
+            count_splits(&parsed, EdgePolicy::Lost),
+            (
+                1,
+                Exits {
+                    left: 1,
+                    right: 1,
+                    ..Exits::default()
+                }
+            )
+        );
 
-        rows += 1;
-        for (col, ch) in line.chars().enumerate() {
-            match ch {
-                'S' => start = Some((row, col)),
-                '^' => carets.push((row, col)),
-                _ => {}
+        let test_input = r#"
+S..
+...
+\.#
+"#;
+        let parsed = parse_manifold(test_input);
+        assert_eq!(
+            count_timelines::<usize>(&parsed, EdgePolicy::Lost).unwrap(),
+            Exits {
+                absorbed: 1,
+                ..Exits::default()
             }
-        }
+        );
     }
 
-    (start, carets, rows)
-}
+    #[test]
+    fn test_split_halves_meet_their_landing_cell() {
+        // the right half lands on the absorber instead of passing through it
+        let parsed = parse_manifold(".S..\n....\n.^#.\n....\n");
+        assert_eq!(
+            count_splits(&parsed, EdgePolicy::Lost),
+            (
+                1,
+                Exits {
+                    absorbed: 1,
+                    bottom: 1,
+                    ..Exits::default()
+                }
+            )
+        );
+
+        // the left half is turned left by the mirror and leaves the side
+        let parsed = parse_manifold(".S..\n....\n/^..\n....\n");
+        assert_eq!(
+            count_timelines::<usize>(&parsed, EdgePolicy::Lost).unwrap(),
+            Exits {
+                left: 1,
+                bottom: 1,
+                ..Exits::default()
+            }
+        );
+
+        // the right half is split by `-`; its left part comes back into the `^` from the side,
+        // which splits it up and down
+        let parsed = parse_manifold(".S.\n...\n.^-\n...\n");
+        assert_eq!(
+            count_timelines::<usize>(&parsed, EdgePolicy::Lost).unwrap(),
+            Exits {
+                left: 2,
+                right: 1,
+                bottom: 1,
+                ..Exits::default()
+            }
+        );
+        assert_eq!(count_splits(&parsed, EdgePolicy::Lost).0, 2);
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+        let heatmap = timeline_heatmap::<usize>(&parsed, EdgePolicy::Lost).unwrap();
+        assert_eq!(*heatmap.splitter_hits(2, 2), 1);
+        assert_eq!(*heatmap.splitter_hits(2, 1), 2);
+        assert_eq!(*heatmap.timelines(2, 2), 1);
+    }
 
     #[test]
-    fn test_part_one_example_input() {
+    fn test_loops_are_detected() {
+        // both halves of the split circle the mirror loop forever
         let test_input = r#"
-.......S.......
-...............
-.......^.......
-...............
-......^.^......
-...............
-.....^.^.^.....
-...............
-....^.^...^....
-...............
-...^.^...^.^...
-...............
-..^...^.....^..
-...............
-.^.^.^.^.^...^.
-...............
+..S..
+./-\.
+.....
+.\./.
 "#;
-
         let parsed = parse_manifold(test_input);
-        assert_eq!(count_splits(&parsed), 21);
+
+        assert!(matches!(
+            count_timelines::<usize>(&parsed, EdgePolicy::Lost),
+            Err(TraceError::Cycle { .. })
+        ));
+        assert_eq!(
+            count_splits(&parsed, EdgePolicy::Lost),
+            (1, Exits::default())
+        );
     }
 
     #[test]
-    fn test_part_two_example_input() {
-        let test_input = r#"
-.......S.......
-...............
-.......^.......
-...............
-......^.^......
-...............
-.....^.^.^.....
-...............
-....^.^...^....
-...............
-...^.^...^.^...
-...............
-..^...^.....^..
-...............
-.^.^.^.^.^...^.
-...............
-"#;
-
-        let parsed = parse_manifold(test_input);
-        assert_eq!(count_timelines(&parsed), 40);
+    fn test_counts_past_fixed_width() {
+        // every splitter row doubles the timelines, wrapping keeps them all inside
+        let doubling = |rows: usize| format!("S...\n{}", "^^^^\n".repeat(rows));
+
+        let parsed = parse_manifold(&doubling(40));
+        assert_eq!(
+            count_timelines::<u32>(&parsed, EdgePolicy::Wrap),
+            Err(TraceError::Overflow)
+        );
+        assert_eq!(
+            count_timelines::<u64>(&parsed, EdgePolicy::Wrap)
+                .unwrap()
+                .bottom,
+            1 << 40
+        );
+
+        let parsed = parse_manifold(&doubling(70));
+        assert_eq!(
+            count_timelines::<u64>(&parsed, EdgePolicy::Wrap),
+            Err(TraceError::Overflow)
+        );
+        assert_eq!(
+            count_timelines::<u128>(&parsed, EdgePolicy::Wrap)
+                .unwrap()
+                .bottom,
+            1 << 70
+        );
+
+        let exact = count_timelines::<BigCount>(&parsed, EdgePolicy::Wrap).unwrap();
+        assert_eq!(exact.bottom.to_string(), "1180591620717411303424");
+        assert_eq!(exact.total().unwrap(), exact.bottom);
+
+        let modular =
+            count_timelines::<ModCount<1_000_000_007>>(&parsed, EdgePolicy::Wrap).unwrap();
+        assert_eq!(modular.bottom.value(), 270016253);
+
+        // the tracer counts the same way
+        let traced = parse_manifold(&format!("#...\n{}", doubling(70)));
+        assert_eq!(
+            count_timelines::<BigCount>(&traced, EdgePolicy::Wrap).unwrap(),
+            exact
+        );
+        assert_eq!(BigCount::from(40).to_string(), "40");
+        assert_eq!(BigCount::default().to_string(), "0");
     }
 }
@@ -1,16 +1,52 @@
 use aoc2025::day08::*;
 
 fn main() {
-    let junction_boxes = parse_locations(INPUT);
+    let junction_boxes = parse_locations(INPUT).unwrap_or_else(|err| panic!("{err}"));
 
-    let res_one = calculate_circuits(&junction_boxes, 1000);
-    let res_two = calculate_extension_size(&junction_boxes);
+    // `cargo run --bin day08 -- [metric] [timeline]`; the timeline keeps every pair up to the
+    // last join, so it is only built when asked for
+    let mut args = std::env::args().skip(1);
+    let metric = args.next();
+    let timeline = match args.next().as_deref() {
+        None => false,
+        Some("timeline") => true,
+        Some(other) => panic!("unknown option {other:?}, expected timeline"),
+    };
+
+    match metric.as_deref() {
+        None | Some("euclidean") => report(&junction_boxes, &Euclidean, timeline),
+        Some("manhattan") => report(&junction_boxes, &Manhattan, timeline),
+        Some("chebyshev") => report(&junction_boxes, &Chebyshev, timeline),
+        Some(other) => {
+            panic!("unknown metric {other:?}, expected euclidean, manhattan or chebyshev")
+        }
+    }
+}
+
+fn report(junction_boxes: &[JunctionBox], metric: &impl Metric, timeline: bool) {
+    let res_one = calculate_circuits(junction_boxes, metric, 1000);
+    let res_two = calculate_extension_size(junction_boxes, metric);
 
     println!("The first three longest circuits multiply to: {}", res_one);
     println!(
         "The extension size needed for connecting the last two elements is: {}",
         res_two
     );
+
+    if !timeline {
+        return;
+    }
+    let timeline = connection_timeline(junction_boxes, metric, usize::MAX);
+    let redundant = timeline
+        .steps()
+        .iter()
+        .filter(|step| step.redundant)
+        .count();
+    println!(
+        "Every junction box is connected after {} pairs, {} of them redundant",
+        timeline.steps().len(),
+        redundant
+    );
 }
 
 #[cfg(test)]
@@ -19,15 +55,56 @@ mod tests {
 
     #[test]
     fn test_day08_part_one_example_input() {
-        let junction_boxes = parse_locations(TEST_INPUT);
+        let junction_boxes = parse_locations(TEST_INPUT).unwrap();
 
-        assert_eq!(calculate_circuits(&junction_boxes, 10), 40);
+        assert_eq!(calculate_circuits(&junction_boxes, &Euclidean, 10), 40);
     }
 
     #[test]
     fn test_day08_part_two_example_input() {
-        let junction_boxes = parse_locations(TEST_INPUT);
+        let junction_boxes = parse_locations(TEST_INPUT).unwrap();
+
+        assert_eq!(calculate_extension_size(&junction_boxes, &Euclidean), 25272);
+    }
+
+    #[test]
+    fn test_connection_timeline() {
+        let junction_boxes = parse_locations(TEST_INPUT).unwrap();
+        let timeline = connection_timeline(&junction_boxes, &Euclidean, usize::MAX);
+
+        // 162,817,812 and 425,690,689 are the closest pair
+        let first = timeline.after_pairs(1).unwrap();
+        assert_eq!((first.l_box, first.r_box), (0, 19));
+        assert_eq!((first.circuits, first.largest), (19, 2));
+
+        let tenth = timeline.after_pairs(10).unwrap();
+        assert_eq!((tenth.circuits, tenth.largest), (11, 5));
+        assert_eq!(
+            timeline.steps()[..10]
+                .iter()
+                .filter(|step| step.redundant)
+                .count(),
+            1
+        );
+
+        // the closest pair is about 316.9 apart, the next one about 321.6
+        assert_eq!(timeline.within_distance(316), None);
+        assert_eq!(timeline.within_distance(317), Some(first));
+        assert_eq!(timeline.within_distance(322), timeline.after_pairs(2));
+
+        assert!(timeline.fully_connected());
+        let last = timeline.steps().last().unwrap();
+        assert_eq!(timeline.last_join(), Some(last));
+        assert_eq!(last.circuits, 1);
+        assert_eq!(
+            junction_boxes.len() - 1,
+            timeline.steps().iter().filter(|s| !s.redundant).count()
+        );
 
-        assert_eq!(calculate_extension_size(&junction_boxes), 25272);
+        // part two streams the same pairs without keeping them
+        assert_eq!(
+            junction_boxes[last.l_box].x() as i128 * junction_boxes[last.r_box].x() as i128,
+            calculate_extension_size(&junction_boxes, &Euclidean)
+        );
     }
 }
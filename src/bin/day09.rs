@@ -1,26 +1,191 @@
 use aoc2025::day09::*;
+use aoc2025::time_it;
 
 fn main() {
-    let parsed_input = parse_locations(INPUT);
+    let parsed_input = parse_locations(INPUT).unwrap_or_else(|err| panic!("{err}"));
 
-    let res_one = largest_area(&parsed_input);
-    // let res_two = time_it!("part two: ", count_timelines(&parsed));
+    let res_one = time_it!("part one: ", largest_area(&parsed_input));
+    let res_two = time_it!("part two: ", largest_inside_area(&parsed_input))
+        .unwrap_or_else(|err| panic!("{err}"));
 
-    println!("The largest rectangle size is {}", res_one);
-    // println!("The particle would generate {} timelines", res_two);
+    for (label, corners) in [("", res_one), (" of red and green tiles", res_two)] {
+        match corners {
+            Some(corners) => println!(
+                "The largest rectangle{label} is {} ({},{} to {},{})",
+                corners.tiles(),
+                corners.a.x,
+                corners.a.y,
+                corners.b.x,
+                corners.b.y
+            ),
+            None => println!("There is no rectangle{label}"),
+        }
+    }
+
+    // `cargo run --bin day09 -- svg > floor.svg`; ascii is refused for floors as large as the input
+    match std::env::args().nth(1).as_deref() {
+        None => {}
+        Some("svg") => print!("{}", render_svg(&parsed_input, res_two.as_ref(), 800.0)),
+        Some("ascii") => print!(
+            "{}",
+            render_ascii(&parsed_input, res_two.as_ref()).unwrap_or_else(|err| panic!("{err}"))
+        ),
+        Some(other) => panic!("unknown rendering {other:?}, expected svg or ascii"),
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use aoc2025::geometry::Rectangle;
+    use aoc2025::lcg::Lcg;
 
     #[test]
     fn test_part_one_example_input() {
-        let parsed_input = parse_locations(TEST_INPUT);
+        let parsed_input = parse_locations(TEST_INPUT).unwrap();
+
+        assert_eq!(largest_area(&parsed_input).map(|c| c.tiles()), Some(50));
+    }
+
+    #[test]
+    fn test_part_two_example_input() {
+        let parsed_input = parse_locations(TEST_INPUT).unwrap();
+
+        let corners = largest_inside_area(&parsed_input).unwrap().unwrap();
+        assert_eq!(corners.tiles(), 24);
+        let expected = "\
+............
+......#XXX#.
+......XXXXX.
+.#OOOO#OOXX.
+.OOOOOOOOXX.
+.#OOOOOO#XX.
+........XXX.
+........#X#.
+............
+";
+        assert_eq!(
+            render_ascii(&parsed_input, Some(&corners)),
+            Ok(expected.to_string())
+        );
+        assert_eq!(
+            render_ascii(&parse_locations(INPUT).unwrap(), None),
+            Err(RenderError::TooLarge {
+                width: 96_802,
+                height: 96_889
+            })
+        );
+
+        let svg = render_svg(&parsed_input, Some(&corners), 120.0);
+        assert!(svg.starts_with("<svg"));
+        assert_eq!(svg.matches("<circle").count(), parsed_input.len());
+        assert!(svg.contains("<rect x=\"0.00\" y=\"24.00\" width=\"96.00\" height=\"36.00\""));
+    }
+
+    #[test]
+    fn test_rectangles_cannot_span_a_notch() {
+        let test_input = r#"
+0,0
+10,0
+10,10
+7,10
+7,3
+3,3
+3,10
+0,10
+"#;
+        let parsed_input = parse_locations(test_input).unwrap();
+
+        assert_eq!(largest_area(&parsed_input).map(|c| c.tiles()), Some(121));
+        // either arm of the U, the notch between them is outside the loop
+        assert_eq!(
+            largest_inside_area(&parsed_input).map(|c| c.map(|c| c.tiles())),
+            Ok(Some(44))
+        );
+    }
+
+    #[test]
+    fn test_parse_errors_point_at_the_line() {
+        assert_eq!(
+            parse_locations("7,1\n11,1\n\n11 7\n")
+                .unwrap_err()
+                .to_string(),
+            "section `red tiles`, line 4: \"11 7\": expected `x,y`"
+        );
+        assert_eq!(parse_locations("7,1\n11,x\n").unwrap_err().line, 2);
+    }
 
-        assert_eq!(largest_area(&parsed_input), 50);
+    fn largest_area_brute_force(tiles: &[Location]) -> u64 {
+        let mut max = 0;
+        for (idx, lhs) in tiles.iter().enumerate() {
+            for rhs in &tiles[idx + 1..] {
+                max = max.max(Rectangle::from_corners(*lhs, *rhs).tiles());
+            }
+        }
+        max
     }
 
     #[test]
-    fn test_part_two_example_input() {}
+    fn test_staircase_search_matches_brute_force() {
+        let mut rng = Lcg::new(42);
+
+        for _ in 0..500 {
+            let count = rng.index(60);
+            let spread = 1 + rng.below(1000) as i64; // small spreads give plenty of ties
+            let tiles: Vec<Location> = (0..count)
+                .map(|_| {
+                    let x = rng.below(spread as u64) as i64 - spread / 2;
+                    let y = rng.below(spread as u64) as i64 - spread / 2;
+                    Location::new(x, y)
+                })
+                .collect();
+
+            let corners = largest_area(&tiles);
+            assert_eq!(
+                corners.map_or(0, |c| c.tiles()),
+                largest_area_brute_force(&tiles),
+                "{tiles:?}"
+            );
+            assert!(corners.is_none_or(|c| tiles.contains(&c.a) && tiles.contains(&c.b)));
+        }
+
+        assert_eq!(largest_area(&[Location::new(3, 4)]), None);
+        let twins = [Location::new(3, 4), Location::new(3, 4)];
+        assert_eq!(largest_area(&twins).map(|c| c.tiles()), Some(1));
+    }
+
+    #[test]
+    fn test_staircase_search_on_convex_layouts() {
+        let mut rng = Lcg::new(2025);
+
+        for round in 0..300 {
+            // ellipses (the puzzle's tiles lie on a rough circle), and parabolas, so nearly every
+            // tile is on a staircase
+            let count = 2 + rng.index(150);
+            let (rx, ry) = (1 + rng.below(5000) as i64, 1 + rng.below(5000) as i64);
+            let (cx, cy) = (rng.below(2000) as i64 - 1000, rng.below(2000) as i64 - 1000);
+            let tiles: Vec<Location> = (0..count)
+                .map(|_| {
+                    let t = rng.below(1 << 20) as f64 / (1 << 20) as f64;
+                    if round % 3 == 2 {
+                        let x = (t * 2.0 - 1.0) * rx as f64;
+                        Location::new(cx + x as i64, cy + (x * x / rx as f64) as i64)
+                    } else {
+                        let angle = t * std::f64::consts::TAU;
+                        let x = cx + (angle.cos() * rx as f64).round() as i64;
+                        let y = cy + (angle.sin() * ry as f64).round() as i64;
+                        Location::new(x, y)
+                    }
+                })
+                .collect();
+
+            let corners = largest_area(&tiles).unwrap();
+            assert_eq!(
+                corners.tiles(),
+                largest_area_brute_force(&tiles),
+                "{tiles:?}"
+            );
+            assert!(tiles.contains(&corners.a) && tiles.contains(&corners.b));
+        }
+    }
 }
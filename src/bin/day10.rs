@@ -1,26 +1,359 @@
 use aoc2025::day10::*;
+use aoc2025::time_it;
 
 fn main() {
-    let parsed_input = parse_manual(INPUT);
+    let parsed_input = parse_manual(INPUT).unwrap_or_else(|err| panic!("{err}"));
 
-    let res_one = fewest_buttons_all_machines(&parsed_input);
-    // let res_two = time_it!("part two: ", count_timelines(&parsed));
+    let res_one = fewest_buttons_all_machines(&parsed_input).unwrap_or_else(|err| panic!("{err}"));
+    let res_two = time_it!("part two: ", fewest_presses_all_machines(&parsed_input))
+        .unwrap_or_else(|err| panic!("{err}"));
 
     println!("The sum of all fewest button presses is {}", res_one);
-    // println!("The particle would generate {} timelines", res_two);
+    println!(
+        "The fewest presses reaching every joltage sum to {}",
+        res_two
+    );
+
+    // `cargo run --bin day10 -- analyze` for what elimination learned about each machine's lights
+    match std::env::args().nth(1).as_deref() {
+        None => {}
+        Some("analyze") => {
+            for (idx, line) in parsed_input.iter().enumerate() {
+                let analysis = analyze(line).unwrap_or_else(|err| panic!("{err}"));
+                print!(
+                    "machine {idx}: rank {} of {} buttons, redundant {:?}",
+                    analysis.rank(),
+                    line.buttons.len(),
+                    analysis.redundant_buttons()
+                );
+                match analysis.optimal_count() {
+                    Ok(count) => println!(", {count} optimal press sets"),
+                    Err(err) => println!(", {err}"),
+                }
+            }
+        }
+        Some(other) => panic!("unknown report {other:?}, expected analyze"),
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use aoc2025::lcg::Lcg;
 
     #[test]
     fn test_part_one_example_input() {
-        let parsed_input = parse_manual(TEST_INPUT);
+        let parsed_input = parse_manual(TEST_INPUT).unwrap();
+
+        assert_eq!(fewest_buttons_all_machines(&parsed_input), Ok(7));
+    }
+
+    #[test]
+    fn test_part_two_example_input() {
+        let parsed_input = parse_manual(TEST_INPUT).unwrap();
+
+        assert_eq!(parsed_input[0].joltage, vec![3, 5, 4, 7]);
+        assert_eq!(
+            min_joltage_presses(&parsed_input[0]).map(|p| p.iter().sum()),
+            Ok(10)
+        );
+        assert_eq!(fewest_presses_all_machines(&parsed_input), Ok(33));
+    }
+
+    // every press vector within the bounds, fewest presses first
+    fn min_joltage_brute_force(line: &Line) -> Option<u64> {
+        let bound = line.joltage.iter().copied().max().unwrap_or(0);
+        let mut presses = vec![0u64; line.buttons.len()];
+        let mut best = None;
+
+        loop {
+            let mut counters = vec![0; line.joltage.len()];
+            for (button, &count) in line.buttons.iter().zip(&presses) {
+                for &counter in button {
+                    counters[counter] += count;
+                }
+            }
+            if counters == line.joltage {
+                let total = presses.iter().sum::<u64>();
+                best = Some(best.map_or(total, |best: u64| best.min(total)));
+            }
+
+            // next press vector, like counting in base bound + 1
+            let Some(idx) = presses.iter().position(|&count| count < bound) else {
+                return best;
+            };
+            presses[..idx].fill(0);
+            presses[idx] += 1;
+        }
+    }
+
+    #[test]
+    fn test_joltage_matches_brute_force() {
+        let mut rng = Lcg::new(5);
+
+        for _ in 0..200 {
+            let counters = 1 + rng.index(4);
+            let line = Line {
+                lights: vec![false; counters],
+                buttons: (0..1 + rng.index(4))
+                    .map(|_| (0..1 + rng.index(3)).map(|_| rng.index(counters)).collect())
+                    .collect(),
+                joltage: (0..counters).map(|_| rng.below(6)).collect(),
+            };
+
+            let solved = min_joltage_presses(&line);
+            match min_joltage_brute_force(&line) {
+                Some(total) => {
+                    let presses = solved.unwrap();
+                    assert_eq!(presses.iter().sum::<u64>(), total, "{line:?}");
+                }
+                None => assert_eq!(solved, Err(ConfigError::Unreachable), "{line:?}"),
+            }
+        }
+    }
+
+    // every subset of buttons, fewest presses first
+    fn min_buttons_brute_force(line: &Line) -> Option<usize> {
+        let m = line.buttons.len();
+        (0u64..1 << m)
+            .filter(|subset| {
+                let mut lights = vec![false; line.lights.len()];
+                for (idx, button) in line.buttons.iter().enumerate() {
+                    if subset >> idx & 1 == 1 {
+                        for &light in button {
+                            lights[light] = !lights[light];
+                        }
+                    }
+                }
+                lights == line.lights
+            })
+            .map(|subset| subset.count_ones() as usize)
+            .min()
+    }
+
+    #[test]
+    fn test_elimination_matches_brute_force() {
+        let mut rng = Lcg::new(3);
+
+        for _ in 0..300 {
+            let lights = 1 + rng.index(8);
+            let line = Line {
+                lights: (0..lights).map(|_| rng.index(2) == 1).collect(),
+                buttons: (0..rng.index(9))
+                    .map(|_| (0..1 + rng.index(4)).map(|_| rng.index(lights)).collect())
+                    .collect(),
+                joltage: Vec::new(),
+            };
+
+            let solved = min_buttons(&line);
+            match min_buttons_brute_force(&line) {
+                Some(count) => {
+                    let pressed = solved.unwrap();
+                    assert_eq!(pressed.len(), count, "{line:?}");
+                    assert_eq!(
+                        min_buttons_brute_force(&Line {
+                            buttons: pressed.iter().map(|&b| line.buttons[b].clone()).collect(),
+                            ..line.clone()
+                        }),
+                        Some(count)
+                    );
+                }
+                None => assert_eq!(solved, Err(ConfigError::Unreachable), "{line:?}"),
+            }
+        }
+    }
+
+    #[test]
+    fn test_analysis() {
+        let parsed_input = parse_manual(TEST_INPUT).unwrap();
+
+        let analysis = analyze(&parsed_input[0]).unwrap();
+        assert!(analysis.is_reachable());
+        assert_eq!(analysis.rank(), 4);
+        assert_eq!(analysis.redundant_buttons(), vec![0, 1, 2, 3, 4, 5]);
+        assert_eq!(analysis.fewest_buttons(), Ok(2));
+        assert_eq!(analysis.optimal_count(), Ok(2));
+        let mut optimal: Vec<_> = analysis.optimal_press_sets().unwrap().collect();
+        optimal.sort();
+        assert_eq!(optimal, vec![vec![1, 3], vec![4, 5]]);
+
+        // the same buttons can't light a single light
+        let unreachable = Line {
+            lights: vec![true, false, false, false],
+            buttons: vec![vec![0, 1], vec![1, 2], vec![0, 2], vec![3]],
+            joltage: Vec::new(),
+        };
+        let analysis = analyze(&unreachable).unwrap();
+        assert!(!analysis.is_reachable());
+        assert_eq!(analysis.rank(), 3);
+        assert_eq!(analysis.redundant_buttons(), vec![0, 1, 2]);
+        assert_eq!(analysis.optimal_count(), Err(ConfigError::Unreachable));
+    }
 
-        assert_eq!(fewest_buttons_all_machines(&parsed_input), 7);
+    #[test]
+    fn test_analysis_matches_brute_force() {
+        let mut rng = Lcg::new(9);
+
+        for _ in 0..300 {
+            let lights = 1 + rng.index(6);
+            let line = Line {
+                lights: (0..lights).map(|_| rng.index(2) == 1).collect(),
+                buttons: (0..rng.index(8))
+                    .map(|_| (0..1 + rng.index(3)).map(|_| rng.index(lights)).collect())
+                    .collect(),
+                joltage: Vec::new(),
+            };
+            let toggled = |subset: u64| {
+                let mut toggled = vec![false; lights];
+                for (idx, button) in line.buttons.iter().enumerate() {
+                    if subset >> idx & 1 == 1 {
+                        for &light in button {
+                            toggled[light] = !toggled[light];
+                        }
+                    }
+                }
+                toggled
+            };
+
+            let subsets = 0u64..1 << line.buttons.len();
+            let mut optimal: Vec<Vec<usize>> = Vec::new();
+            for subset in subsets
+                .clone()
+                .filter(|&subset| toggled(subset) == line.lights)
+            {
+                let pressed: Vec<usize> = (0..64).filter(|idx| subset >> idx & 1 == 1).collect();
+                if optimal
+                    .first()
+                    .is_none_or(|first| pressed.len() < first.len())
+                {
+                    optimal.clear();
+                }
+                if optimal
+                    .first()
+                    .is_none_or(|first| pressed.len() == first.len())
+                {
+                    optimal.push(pressed);
+                }
+            }
+            // redundant when some subset of the other buttons toggles the same lights
+            let redundant: Vec<usize> = (0..line.buttons.len())
+                .filter(|&button| {
+                    subsets.clone().any(|subset| {
+                        subset >> button & 1 == 0 && toggled(subset) == toggled(1 << button)
+                    })
+                })
+                .collect();
+            let independent = subsets
+                .clone()
+                .filter(|&subset| subset != 0 && toggled(subset) == vec![false; lights])
+                .count();
+
+            optimal.sort();
+
+            let analysis = analyze(&line).unwrap();
+            assert_eq!(analysis.redundant_buttons(), redundant, "{line:?}");
+            // 2^(buttons - rank) press sets toggle nothing
+            assert_eq!(1 << (line.buttons.len() - analysis.rank()), independent + 1);
+            assert_eq!(analysis.is_reachable(), !optimal.is_empty());
+            if analysis.is_reachable() {
+                let mut press_sets: Vec<_> = analysis.optimal_press_sets().unwrap().collect();
+                press_sets.sort();
+                assert_eq!(press_sets, optimal, "{line:?}");
+                assert_eq!(analysis.optimal_count(), Ok(optimal.len() as u64));
+            }
+        }
     }
 
     #[test]
-    fn test_part_two_example_input() {}
+    fn test_too_many_free_buttons() {
+        // every button toggles the only light: one of them is the pivot, the rest are free
+        let line = Line {
+            lights: vec![true],
+            buttons: vec![vec![0]; MAX_FREE_BUTTONS + 2],
+            joltage: Vec::new(),
+        };
+        let analysis = analyze(&line).unwrap();
+        let too_many = ConfigError::TooManyFreeButtons {
+            free: MAX_FREE_BUTTONS + 1,
+        };
+
+        assert_eq!(analysis.rank(), 1);
+        assert_eq!(analysis.redundant_buttons().len(), MAX_FREE_BUTTONS + 2);
+        assert_eq!(analysis.fewest_buttons(), Err(too_many.clone()));
+        assert_eq!(analysis.optimal_count(), Err(too_many.clone()));
+        assert_eq!(analysis.optimal_press_sets().err(), Some(too_many.clone()));
+        assert_eq!(min_buttons(&line), Err(too_many));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let err = parse_manual("[.##.] (3) {1,2,3,4}\n\n[.#] (0,2) {1,1}\n").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "section `machines`, line 3: \"[.#] (0,2) {1,1}\": column 6: button 0 toggles light 2, but there are only 2 lights"
+        );
+
+        let message = |line: &str| parse_manual(line).unwrap_err().message;
+        let cases = [
+            ("[.#x] (0)", "column 4: light 'x' is neither '.' nor '#'"),
+            ("[.##. (0)", "column 1: '[' is never closed"),
+            ("[.##.] (0,1 (2)", "column 8: '(' is never closed"),
+            ("[.##.] (0)]", "column 11: unexpected ']'"),
+            ("[.##.] (1,3,1)", "column 8: button 0 lists light 1 twice"),
+            (
+                "[.##.] (0) (a)",
+                "column 12: bad light index \"a\": invalid digit found in string",
+            ),
+            (
+                "[.##.] (0) {1,2,3}",
+                "column 12: 3 joltage requirements for 4 lights",
+            ),
+            (
+                "[.##.] (0) {1,2,3,4} (1)",
+                "column 22: '(' after the joltage requirements",
+            ),
+            ("(0) [.##.]", "column 1: '(' before the light diagram"),
+            (
+                "[.##.] {1,2,3,4}",
+                "column 8: joltage requirements before any button",
+            ),
+            ("[.##.]", "column 7: no buttons"),
+            ("[] (0)", "column 1: empty light diagram"),
+        ];
+        for (line, expected) in cases {
+            assert_eq!(message(line), format!("{line:?}: {expected}"));
+        }
+
+        let parsed = parse_manual("\r\n[#] (0)\r\n").unwrap();
+        assert_eq!(parsed[0].joltage, Vec::<u64>::new());
+    }
+
+    #[test]
+    fn test_many_lights_and_buttons() {
+        // 200 lights, button `i` toggles lights i and i + 1: only pressing all of 0..199 lights
+        // up light 0 and light 199 alone
+        let mut lights = vec![false; 200];
+        lights[0] = true;
+        lights[199] = true;
+        let line = Line {
+            lights,
+            buttons: (0..199).map(|i| vec![i, i + 1]).collect(),
+            joltage: Vec::new(),
+        };
+        assert_eq!(min_buttons(&line), Ok((0..199).collect::<Vec<_>>()));
+
+        let mut unreachable = line.clone();
+        unreachable.lights[100] = true;
+        assert_eq!(min_buttons(&unreachable), Err(ConfigError::Unreachable));
+
+        let mut broken = line;
+        broken.buttons.push(vec![200]);
+        assert_eq!(
+            min_buttons(&broken),
+            Err(ConfigError::LightOutOfRange {
+                button: 199,
+                light: 200
+            })
+        );
+    }
 }
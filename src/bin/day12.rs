@@ -1,7 +1,7 @@
 use aoc2025::day12::*;
 
 fn main() {
-    let puzzle = parse_puzzle(INPUT);
+    let puzzle = parse_puzzle(INPUT).unwrap_or_else(|err| panic!("{err}"));
 
     println!(
         "The amount of regions that are solvable are: {}",
@@ -15,10 +15,30 @@ mod tests {
 
     #[test]
     fn test_part_one_example_input() {
-        let puzzle = parse_puzzle(TEST_INPUT);
+        let puzzle = parse_puzzle(TEST_INPUT).unwrap();
         assert_eq!(get_possible_regions(&puzzle), 2);
     }
 
+    #[test]
+    fn test_parse_without_blank_lines() {
+        let compact: String = TEST_INPUT
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| format!("{line}\n"))
+            .collect();
+
+        assert_eq!(
+            parse_puzzle(&compact).unwrap(),
+            parse_puzzle(TEST_INPUT).unwrap()
+        );
+        assert_eq!(
+            parse_puzzle("0:\n##\n1:\n4x4: 1 0\n")
+                .unwrap_err()
+                .to_string(),
+            "section `shape 1`, line 3: shape has no grid"
+        );
+    }
+
     #[test]
     fn test_part_two_example_input() {}
 }
@@ -0,0 +1,286 @@
+/*
+    compressed_grid:
+
+    Lattice point grids whose coordinates run into the hundreds of thousands (day09) are too big
+    to store densely, but they only change at a few coordinates. Each axis is cut at those
+    coordinates into runs, and every cell (a row run by a column run) is either fully filled or
+    fully empty.
+
+    Prefix sums over the cells then count the filled points of any rectangle in O(1) after an
+    O(log n) lookup of its corners. A rectangle whose sides cut through runs only covers part of
+    its border cells, so besides the sums weighted by full cell areas there are ones weighted by
+    run length on a single axis, and plain cell counts; each of the up to 3 x 3 pieces of the
+    rectangle uses the sum matching how its cells are covered.
+
+    Everything outside the cuts counts as empty.
+*/
+use crate::geometry::Rectangle;
+use std::ops::Range;
+
+/// Sorted cut coordinates; run `i` is `cuts[i]..cuts[i + 1]`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Axis {
+    cuts: Vec<i64>,
+}
+
+impl Axis {
+    pub fn new(mut cuts: Vec<i64>) -> Self {
+        cuts.sort_unstable();
+        cuts.dedup();
+        Axis { cuts }
+    }
+
+    pub fn runs(&self) -> usize {
+        self.cuts.len().saturating_sub(1)
+    }
+
+    pub fn run(&self, idx: usize) -> Range<i64> {
+        self.cuts[idx]..self.cuts[idx + 1]
+    }
+
+    /// Run holding `value`, `None` outside the cuts.
+    pub fn index(&self, value: i64) -> Option<usize> {
+        let (first, last) = (*self.cuts.first()?, *self.cuts.last()?);
+        (first..last)
+            .contains(&value)
+            .then(|| self.cuts.partition_point(|&cut| cut <= value) - 1)
+    }
+
+    fn len(&self, idx: usize) -> u128 {
+        self.cuts[idx + 1].abs_diff(self.cuts[idx]) as u128
+    }
+
+    /// Runs covering `lo..=hi` as `(runs, covered length)`, the length `None` for fully covered
+    /// runs. At most a partial first run, full middle runs and a partial last run.
+    fn segments(&self, lo: i64, hi: i64) -> [Option<(Range<usize>, Option<u128>)>; 3] {
+        let (Some(&first), Some(&last)) = (self.cuts.first(), self.cuts.last()) else {
+            return [None, None, None];
+        };
+        let (lo, hi) = (lo.max(first), hi.min(last - 1));
+        if lo > hi {
+            return [None, None, None];
+        }
+
+        let (a, b) = (self.index(lo).unwrap(), self.index(hi).unwrap());
+        if a == b {
+            return [
+                Some((a..a + 1, Some(hi.abs_diff(lo) as u128 + 1))),
+                None,
+                None,
+            ];
+        }
+
+        [
+            Some((a..a + 1, Some(self.cuts[a + 1].abs_diff(lo) as u128))),
+            (a + 1 < b).then(|| (a + 1..b, None)),
+            Some((b..b + 1, Some(hi.abs_diff(self.cuts[b]) as u128 + 1))),
+        ]
+    }
+}
+
+/// Filled lattice points on compressed axes, see the module comment.
+#[derive(Clone, Debug)]
+pub struct CompressedGrid {
+    cols: Axis,
+    rows: Axis,
+    // prefix sums with an extra row and column of zeros, filled cells weighted by:
+    area: Vec<u128>,       // run length times run length
+    row_length: Vec<u128>, // row run length, for columns only partly covered
+    col_length: Vec<u128>, // column run length, for rows only partly covered
+    cells: Vec<u128>,      // 1, for cells partly covered both ways
+}
+
+impl CompressedGrid {
+    /// `filled` holds one flag per cell, row by row.
+    pub fn from_cells(cols: Axis, rows: Axis, filled: &[bool]) -> Self {
+        let (width, height) = (cols.runs(), rows.runs());
+        assert_eq!(filled.len(), width * height, "one flag per cell expected");
+
+        let stride = width + 1;
+        let mut grid = CompressedGrid {
+            area: vec![0; stride * (height + 1)],
+            row_length: vec![0; stride * (height + 1)],
+            col_length: vec![0; stride * (height + 1)],
+            cells: vec![0; stride * (height + 1)],
+            cols,
+            rows,
+        };
+
+        for row in 0..height {
+            for col in 0..width {
+                let weight = filled[row * width + col] as u128;
+                let (dx, dy) = (grid.cols.len(col), grid.rows.len(row));
+
+                let at = (row + 1) * stride + col + 1;
+                for (prefix, value) in [
+                    (&mut grid.area, weight * dx * dy),
+                    (&mut grid.row_length, weight * dy),
+                    (&mut grid.col_length, weight * dx),
+                    (&mut grid.cells, weight),
+                ] {
+                    prefix[at] =
+                        value + prefix[at - 1] + prefix[at - stride] - prefix[at - stride - 1];
+                }
+            }
+        }
+
+        grid
+    }
+
+    /// Every lattice point covered by at least one of `rectangles`.
+    pub fn from_rectangles(rectangles: &[Rectangle]) -> Self {
+        let cuts = |lo: fn(&Rectangle) -> i64, hi: fn(&Rectangle) -> i64| {
+            Axis::new(rectangles.iter().flat_map(|r| [lo(r), hi(r) + 1]).collect())
+        };
+        let cols = cuts(|r| r.min.x, |r| r.max.x);
+        let rows = cuts(|r| r.min.y, |r| r.max.y);
+        let (width, height) = (cols.runs(), rows.runs());
+
+        // difference array over the cells, summed up into how many rectangles cover each
+        let mut coverage = vec![0i64; (width + 1) * (height + 1)];
+        for rectangle in rectangles {
+            let left = cols.index(rectangle.min.x).unwrap();
+            let right = cols.index(rectangle.max.x).unwrap() + 1;
+            let top = rows.index(rectangle.min.y).unwrap();
+            let bottom = rows.index(rectangle.max.y).unwrap() + 1;
+
+            coverage[top * (width + 1) + left] += 1;
+            coverage[top * (width + 1) + right] -= 1;
+            coverage[bottom * (width + 1) + left] -= 1;
+            coverage[bottom * (width + 1) + right] += 1;
+        }
+
+        let mut filled = vec![false; width * height];
+        for row in 0..height {
+            for col in 0..width {
+                let at = row * (width + 1) + col;
+                if col > 0 {
+                    coverage[at] += coverage[at - 1];
+                }
+                if row > 0 {
+                    coverage[at] += coverage[at - width - 1];
+                }
+                if row > 0 && col > 0 {
+                    coverage[at] -= coverage[at - width - 2];
+                }
+                filled[row * width + col] = coverage[at] > 0;
+            }
+        }
+
+        CompressedGrid::from_cells(cols, rows, &filled)
+    }
+
+    pub fn cols(&self) -> &Axis {
+        &self.cols
+    }
+
+    pub fn rows(&self) -> &Axis {
+        &self.rows
+    }
+
+    fn sum(&self, prefix: &[u128], rows: &Range<usize>, cols: &Range<usize>) -> u128 {
+        let stride = self.cols.runs() + 1;
+        let at = |row: usize, col: usize| prefix[row * stride + col];
+
+        at(rows.end, cols.end) + at(rows.start, cols.start)
+            - at(rows.start, cols.end)
+            - at(rows.end, cols.start)
+    }
+
+    /// Filled lattice points inside `rectangle`.
+    pub fn filled_count(&self, rectangle: &Rectangle) -> u128 {
+        let col_segments = self.cols.segments(rectangle.min.x, rectangle.max.x);
+        let row_segments = self.rows.segments(rectangle.min.y, rectangle.max.y);
+
+        let mut count = 0;
+        for (rows, dy) in row_segments.iter().flatten() {
+            for (cols, dx) in col_segments.iter().flatten() {
+                count += match (dy, dx) {
+                    (None, None) => self.sum(&self.area, rows, cols),
+                    (None, Some(dx)) => dx * self.sum(&self.row_length, rows, cols),
+                    (Some(dy), None) => dy * self.sum(&self.col_length, rows, cols),
+                    (Some(dy), Some(dx)) => dx * dy * self.sum(&self.cells, rows, cols),
+                };
+            }
+        }
+
+        count
+    }
+
+    /// Whether every lattice point of `rectangle` is filled.
+    pub fn is_filled(&self, rectangle: &Rectangle) -> bool {
+        let points = rectangle.width() as u128 * rectangle.height() as u128;
+        self.filled_count(rectangle) == points
+    }
+
+    pub fn total_filled(&self) -> u128 {
+        self.area.last().copied().unwrap_or(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geometry::Point;
+    use crate::lcg::Lcg;
+
+    #[test]
+    fn test_counts_match_a_dense_grid() {
+        let mut rng = Lcg::new(11);
+        let rectangle = |rng: &mut Lcg| {
+            let mut corner = || Point::new(rng.below(24) as i64 - 12, rng.below(24) as i64 - 12);
+            Rectangle::from_corners(corner(), corner())
+        };
+
+        for _ in 0..200 {
+            let count = rng.index(5);
+            let rectangles: Vec<_> = (0..count).map(|_| rectangle(&mut rng)).collect();
+            let grid = CompressedGrid::from_rectangles(&rectangles);
+
+            let covered = |x: i64, y: i64| {
+                rectangles
+                    .iter()
+                    .any(|r| (r.min.x..=r.max.x).contains(&x) && (r.min.y..=r.max.y).contains(&y))
+            };
+
+            let total = (-12..12)
+                .flat_map(|x| (-12..12).map(move |y| (x, y)))
+                .filter(|&(x, y)| covered(x, y))
+                .count();
+            assert_eq!(grid.total_filled(), total as u128);
+
+            for _ in 0..20 {
+                let query = rectangle(&mut rng);
+                let points = (query.min.x..=query.max.x)
+                    .flat_map(|x| (query.min.y..=query.max.y).map(move |y| (x, y)));
+                let filled = points.clone().filter(|&(x, y)| covered(x, y)).count();
+
+                assert_eq!(
+                    grid.filled_count(&query),
+                    filled as u128,
+                    "{rectangles:?} {query:?}"
+                );
+                assert_eq!(grid.is_filled(&query), points.count() == filled);
+            }
+        }
+    }
+
+    #[test]
+    fn test_huge_coordinates() {
+        let big = Rectangle::from_corners(Point::new(0, 0), Point::new(99_999, 99_999));
+        let hole = Rectangle::from_corners(Point::new(500, 500), Point::new(500, 500));
+        let far = Rectangle::from_corners(Point::new(1 << 40, 0), Point::new(1 << 40, 0));
+        let grid = CompressedGrid::from_rectangles(&[big, far]);
+
+        assert_eq!(grid.total_filled(), 100_000 * 100_000 + 1);
+        assert!(grid.is_filled(&big));
+        assert!(grid.is_filled(&hole));
+        assert!(!grid.is_filled(&Rectangle::from_corners(
+            Point::new(0, 0),
+            Point::new(100_000, 0)
+        )));
+        assert_eq!(grid.cols().runs(), 3);
+        assert_eq!(grid.cols().index(-1), None);
+        assert_eq!(grid.cols().run(1), 100_000..1 << 40);
+    }
+}
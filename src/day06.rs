@@ -0,0 +1,473 @@
+/*
+    day06:
+
+    I need to help cephalopods do their math homework
+
+    The puzzle input consists of a list of problems.
+    Each problem has a group of numbers that need to be either added (+) or multiplied (*)
+    Problems are arranged vertically and the last line is the symbol for the operation.
+
+    Part 1:
+
+    What is the grand total found by adding together all of the answers of the individual problems?
+
+    Part 2:
+
+    The final calculation remains the same, however the numbers are read differently.
+    every column is a number with its most sgnificat digit at the top. e.g.
+
+    12
+    157
+    1
+
+    is read as 7, 25, 111
+
+    Worksheet:
+
+    Besides + and *, the evaluator knows - / min max (folded left to right) and accepts custom
+    operators (`Worksheet::with_operator`). Unknown symbols, overflow and division by zero are
+    reported per problem.
+    Both parts share one layout model; only the reading direction of the digits changes, and
+    part two can be read any way (`cargo run --bin day06 -- bottom-to-top`, `right-to-left` or
+    `rows`). A space between two digits is an error, not part of the number.
+
+    Generate mode (`cargo run --bin day06 -- generate <problems> [seed]`):
+
+    Prints a random worksheet in the row-wise layout, handy for benchmark inputs.
+*/
+use crate::lcg::Lcg;
+use std::collections::HashMap;
+use std::fmt;
+
+pub const INPUT: &str = include_str!("inputs/day06.txt");
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Problem {
+    pub numbers: Vec<i64>,
+    pub operator: String,
+}
+
+/// Combines the accumulated value with the next number, `None` on overflow or invalid input.
+///
+/// Boxed so operators can capture state, a modulus say.
+pub type Fold = Box<dyn Fn(i128, i128) -> Option<i128>>;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum EvalError {
+    UnknownOperator {
+        problem: usize,
+        symbol: String,
+    },
+    NoNumbers {
+        problem: usize,
+    },
+    /// Overflow, division by zero or any other case the operator rejects.
+    Undefined {
+        problem: usize,
+        symbol: String,
+    },
+    TotalOverflow,
+}
+
+impl fmt::Display for EvalError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EvalError::UnknownOperator { problem, symbol } => {
+                write!(f, "problem {problem}: unknown operator {symbol:?}")
+            }
+            EvalError::NoNumbers { problem } => write!(f, "problem {problem}: no numbers"),
+            EvalError::Undefined { problem, symbol } => {
+                write!(
+                    f,
+                    "problem {problem}: {symbol:?} overflowed or is undefined"
+                )
+            }
+            EvalError::TotalOverflow => write!(f, "grand total overflowed"),
+        }
+    }
+}
+
+impl std::error::Error for EvalError {}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum LayoutError {
+    /// The input is empty, or its last line holds numbers rather than operators.
+    NoOperators,
+    /// The columns `start..end` hold numbers but nothing in the operator row below them.
+    MissingOperator {
+        start: usize,
+        end: usize,
+    },
+    MoreThanOneOperator {
+        start: usize,
+        end: usize,
+    },
+    /// A space between two digits, which would otherwise join two numbers into one.
+    SplitNumber {
+        problem: usize,
+        digits: String,
+    },
+    InvalidNumber {
+        problem: usize,
+        digits: String,
+    },
+}
+
+impl fmt::Display for LayoutError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LayoutError::NoOperators => write!(f, "no operator row found"),
+            LayoutError::MissingOperator { start, end } => {
+                write!(f, "no operator below columns {start}..{end}")
+            }
+            LayoutError::MoreThanOneOperator { start, end } => {
+                write!(f, "more than one operator below columns {start}..{end}")
+            }
+            LayoutError::SplitNumber { problem, digits } => {
+                write!(
+                    f,
+                    "problem {problem}: number {digits:?} has a space between its digits"
+                )
+            }
+            LayoutError::InvalidNumber { problem, digits } => {
+                write!(f, "problem {problem}: invalid number {digits:?}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for LayoutError {}
+
+/// Evaluates problems with checked `i128` arithmetic, so results may be negative or exceed `u64`.
+pub struct Worksheet {
+    operators: HashMap<String, Fold>,
+}
+
+impl Default for Worksheet {
+    fn default() -> Self {
+        Worksheet {
+            operators: HashMap::new(),
+        }
+        .with_operator("+", i128::checked_add)
+        .with_operator("-", i128::checked_sub)
+        .with_operator("*", i128::checked_mul)
+        .with_operator("/", i128::checked_div)
+        .with_operator("min", |a, b| Some(a.min(b)))
+        .with_operator("max", |a, b| Some(a.max(b)))
+    }
+}
+
+impl Worksheet {
+    /// Registers (or replaces) the operator written as `symbol`.
+    pub fn with_operator(
+        mut self,
+        symbol: &str,
+        fold: impl Fn(i128, i128) -> Option<i128> + 'static,
+    ) -> Self {
+        self.operators.insert(symbol.to_string(), Box::new(fold));
+        self
+    }
+
+    fn evaluate_one(&self, idx: usize, problem: &Problem) -> Result<i128, EvalError> {
+        let fold =
+            self.operators
+                .get(&problem.operator)
+                .ok_or_else(|| EvalError::UnknownOperator {
+                    problem: idx,
+                    symbol: problem.operator.clone(),
+                })?;
+
+        let (first, rest) = problem
+            .numbers
+            .split_first()
+            .ok_or(EvalError::NoNumbers { problem: idx })?;
+
+        rest.iter().try_fold(*first as i128, |acc, &x| {
+            fold(acc, x as i128).ok_or_else(|| EvalError::Undefined {
+                problem: idx,
+                symbol: problem.operator.clone(),
+            })
+        })
+    }
+
+    /// Per-problem results, in input order.
+    pub fn evaluate(&self, problems: &[Problem]) -> Vec<Result<i128, EvalError>> {
+        problems
+            .iter()
+            .enumerate()
+            .map(|(idx, problem)| self.evaluate_one(idx, problem))
+            .collect()
+    }
+
+    /// Sum of every result, or the error of the first problem that failed.
+    pub fn grand_total(&self, problems: &[Problem]) -> Result<i128, EvalError> {
+        self.evaluate(problems)
+            .into_iter()
+            .try_fold(0i128, |total, result| {
+                total.checked_add(result?).ok_or(EvalError::TotalOverflow)
+            })
+    }
+}
+
+/// How the digits of a block are turned into numbers.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Reading {
+    /// Each row of the block is a number (part one).
+    Rows,
+    /// Each character column is a number, most significant digit at the top, columns left to right.
+    TopToBottom,
+    /// Each character column is a number, most significant digit at the bottom.
+    BottomToTop,
+    /// Like `TopToBottom`, but columns are taken right to left.
+    RightToLeft,
+}
+
+/// A problem: the columns `start..end` of the number rows and the operator written below them.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Block {
+    pub start: usize,
+    pub end: usize,
+    pub operator: String,
+}
+
+/// The worksheet as an aligned character grid, split into problem blocks.
+///
+/// Blocks are runs of columns that are not blank in every line (operator row included),
+/// each holding exactly one operator token.
+#[derive(Clone, Debug)]
+pub struct Layout {
+    rows: Vec<Vec<u8>>, // number rows, padded to the same width
+    blocks: Vec<Block>,
+}
+
+impl Layout {
+    pub fn parse(input: &str) -> Result<Layout, LayoutError> {
+        // 1) Collect non-empty lines, trimming only the right side to keep the alignment.
+        let mut lines: Vec<&str> = input
+            .lines()
+            .map(str::trim_end)
+            .filter(|l| !l.is_empty())
+            .collect();
+
+        // 2) Last line: contains the operators, unless the worksheet has none at all.
+        let ops_line = lines.pop().ok_or(LayoutError::NoOperators)?.as_bytes();
+        if ops_line
+            .iter()
+            .all(|b| b.is_ascii_digit() || b.is_ascii_whitespace())
+        {
+            return Err(LayoutError::NoOperators);
+        }
+
+        // 3) Pad all number lines to the same width (so indexing is safe).
+        let width = lines
+            .iter()
+            .map(|l| l.len())
+            .max()
+            .unwrap_or(0)
+            .max(ops_line.len());
+
+        let rows: Vec<Vec<u8>> = lines
+            .into_iter()
+            .map(|l| {
+                let mut row = l.as_bytes().to_vec();
+                row.resize(width, b' ');
+                row
+            })
+            .collect();
+
+        let is_blank = |col: usize| {
+            rows.iter().all(|row| row[col] == b' ')
+                && ops_line.get(col).is_none_or(|b| b.is_ascii_whitespace())
+        };
+
+        // 4) Every run of non-blank columns is a block with exactly one operator.
+        let mut blocks = Vec::new();
+        let mut col = 0;
+        while col < width {
+            if is_blank(col) {
+                col += 1;
+                continue;
+            }
+
+            let start = col;
+            while col < width && !is_blank(col) {
+                col += 1;
+            }
+
+            let ops = String::from_utf8_lossy(
+                &ops_line[start.min(ops_line.len())..col.min(ops_line.len())],
+            );
+            let mut tokens = ops.split_whitespace();
+            let operator = tokens
+                .next()
+                .ok_or(LayoutError::MissingOperator { start, end: col })?
+                .to_string();
+            if tokens.next().is_some() {
+                return Err(LayoutError::MoreThanOneOperator { start, end: col });
+            }
+
+            blocks.push(Block {
+                start,
+                end: col,
+                operator,
+            });
+        }
+
+        Ok(Layout { rows, blocks })
+    }
+
+    pub fn blocks(&self) -> &[Block] {
+        &self.blocks
+    }
+
+    pub fn problems(&self, reading: Reading) -> Result<Vec<Problem>, LayoutError> {
+        self.blocks
+            .iter()
+            .enumerate()
+            .map(|(idx, block)| {
+                let numbers = self
+                    .read_block(block, reading)
+                    .into_iter()
+                    .filter_map(|cells| to_number(idx, &cells).transpose())
+                    .collect::<Result<_, _>>()?;
+                Ok(Problem {
+                    numbers,
+                    operator: block.operator.clone(),
+                })
+            })
+            .collect()
+    }
+
+    /// The cells of every number in `block`, in reading order.
+    fn read_block(&self, block: &Block, reading: Reading) -> Vec<Vec<u8>> {
+        let column = |col: usize| self.rows.iter().map(|row| row[col]).collect::<Vec<_>>();
+
+        match reading {
+            Reading::Rows => self
+                .rows
+                .iter()
+                .map(|row| row[block.start..block.end].to_vec())
+                .collect(),
+            Reading::TopToBottom => (block.start..block.end).map(column).collect(),
+            Reading::BottomToTop => (block.start..block.end)
+                .map(|col| column(col).into_iter().rev().collect())
+                .collect(),
+            Reading::RightToLeft => (block.start..block.end).rev().map(column).collect(),
+        }
+    }
+}
+
+/// Reads the digits of `cells` in order, ignoring the padding around them; `None` when there
+/// are none.
+fn to_number(problem: usize, cells: &[u8]) -> Result<Option<i64>, LayoutError> {
+    let Some(first) = cells.iter().position(|&b| b != b' ') else {
+        return Ok(None);
+    };
+    let last = cells.iter().rposition(|&b| b != b' ').unwrap();
+
+    let digits = String::from_utf8_lossy(&cells[first..=last]).into_owned();
+    if digits.contains(' ') {
+        return Err(LayoutError::SplitNumber { problem, digits });
+    }
+    match digits.parse::<i64>() {
+        Ok(number) => Ok(Some(number)),
+        Err(_) => Err(LayoutError::InvalidNumber { problem, digits }),
+    }
+}
+
+/// Random `+`/`*`/`-`/`min`/`max` problems with 1 to 5 numbers of up to 6 digits each.
+pub fn generate_problems(count: usize, seed: u64) -> Vec<Problem> {
+    let mut rng = Lcg::new(seed);
+
+    let operators = ["+", "*", "-", "min", "max"];
+    (0..count)
+        .map(|_| Problem {
+            numbers: (0..1 + rng.below(5))
+                .map(|_| 10i64.pow(rng.below(6) as u32) + rng.below(1000) as i64)
+                .collect(),
+            operator: operators[rng.index(operators.len())].to_string(),
+        })
+        .collect()
+}
+
+/// Writes `problems` back as an aligned worksheet that `Layout::parse` reads with `reading`.
+///
+/// Blocks are separated by a single blank column. Numbers must be non-negative since the
+/// worksheet has no room for signs.
+pub fn render_worksheet(problems: &[Problem], reading: Reading) -> String {
+    let digits = |n: i64| {
+        assert!(n >= 0, "cannot write negative number {n} on a worksheet");
+        n.to_string().into_bytes()
+    };
+
+    // Each block as a grid of columns (top to bottom), padded later to the worksheet height.
+    let blocks: Vec<Vec<Vec<u8>>> = problems
+        .iter()
+        .map(|problem| {
+            let numbers = problem.numbers.iter().map(|&n| digits(n));
+
+            match reading {
+                Reading::Rows => {
+                    // One row per number, right-aligned in the block.
+                    let rows: Vec<Vec<u8>> = numbers.collect();
+                    let width = rows.iter().map(Vec::len).max().unwrap_or(0);
+                    (0..width)
+                        .map(|col| {
+                            rows.iter()
+                                .map(|row| {
+                                    let pad = width - row.len();
+                                    if col < pad { b' ' } else { row[col - pad] }
+                                })
+                                .collect()
+                        })
+                        .collect()
+                }
+                Reading::TopToBottom => numbers.collect(),
+                Reading::BottomToTop => numbers.map(|d| d.into_iter().rev().collect()).collect(),
+                Reading::RightToLeft => {
+                    let mut columns: Vec<Vec<u8>> = numbers.collect();
+                    columns.reverse();
+                    columns
+                }
+            }
+        })
+        .collect();
+
+    let height = blocks.iter().flatten().map(Vec::len).max().unwrap_or(0);
+
+    let mut lines = vec![Vec::new(); height + 1];
+    for (idx, (columns, problem)) in blocks.iter().zip(problems).enumerate() {
+        if idx > 0 {
+            for line in &mut lines {
+                line.push(b' ');
+            }
+        }
+
+        let width = columns.len().max(problem.operator.len());
+        for col in 0..width {
+            let cells = columns.get(col).map(Vec::as_slice).unwrap_or_default();
+            let pad = height - cells.len();
+
+            for (row, line) in lines[..height].iter_mut().enumerate() {
+                // Digits read bottom-to-top sit at the bottom of their column.
+                let cell = if reading == Reading::BottomToTop {
+                    row.checked_sub(pad).map(|r| cells[r])
+                } else {
+                    cells.get(row).copied()
+                };
+                line.push(cell.unwrap_or(b' '));
+            }
+        }
+
+        let ops = &mut lines[height];
+        ops.extend_from_slice(problem.operator.as_bytes());
+        ops.resize(ops.len() + width - problem.operator.len(), b' ');
+    }
+
+    let mut out = String::new();
+    for line in lines {
+        out.push_str(String::from_utf8(line).unwrap().trim_end());
+        out.push('\n');
+    }
+    out
+}
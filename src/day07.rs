@@ -0,0 +1,920 @@
+/*
+    day07:
+
+    Our tachyon minifolds have an issue. The diagram of these is our puzzle input.
+    The beam enters the minifold at the location marked 'S', beams always move downward.
+    The beams passe freely through empty spaces '.', However, if they found a splitter (^) the beam is stopped and it splits immediately.
+    They go left and right of the splitter.
+    The process continues until all the tachyoun beams reach a splitter or exit the manifold
+
+    Part one:
+
+    Howe many times will the beam be split?
+
+    Part two:
+
+    After all, it was not a normal tachyon manifold, it was a quantum tachyon manifold! This time it's only a particle that its flowing.
+    The particle could go right or left, each path creating a new timeline.
+
+    How many different timelines would a single tachyon particle end up on?
+
+    Beam tracing:
+
+    Besides the puzzle elements, manifolds may contain `/` and `\` mirrors, `|` and `-` splitters
+    (a beam crossing them splits along their axis, a beam running along them passes), `#`
+    absorbers and several `S` sources, all emitting downward. Beams then travel in all four
+    directions, and `^` sends the two halves one cell to either side of its direction of travel,
+    where the element they land on acts on them (except another `^`).
+    Plain `.S^` manifolds are swept row by row; anything else is traced state by state.
+
+    Counting:
+
+    Timelines double with every splitter row, so counts are generic: fixed-width integers report
+    an overflow error instead of wrapping, `BigCount` is exact at any size, and `ModCount<P>`
+    counts modulo a prime.
+
+    Heatmap:
+
+    In quantum mode, every cell records how many timelines enter it and every splitter how many
+    it split, which can be rendered as ASCII shading or a PGM image.
+*/
+
+use std::fmt;
+
+pub const INPUT: &str = include_str!("inputs/day07.txt");
+
+pub const TEST_INPUT: &str = r#"
+.......S.......
+...............
+.......^.......
+...............
+......^.^......
+...............
+.....^.^.^.....
+...............
+....^.^...^....
+...............
+...^.^...^.^...
+...............
+..^...^.....^..
+...............
+.^.^.^.^.^...^.
+...............
+"#;
+
+/// The manifold as a dense row-major grid, one byte per cell.
+#[derive(Clone, Debug)]
+pub struct Manifold {
+    cells: Vec<u8>,
+    width: usize,
+    height: usize,
+    sources: Vec<(usize, usize)>, // sorted by row
+}
+
+/// What happens to a beam leaving the manifold through the left or right side.
+///
+/// The top and bottom always let beams out.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EdgePolicy {
+    /// The beam leaves the manifold and is counted as an exit on that side.
+    Lost,
+    /// The beam bounces off the wall back into the border column.
+    Reflect,
+    /// The beam comes back in through the opposite side.
+    Wrap,
+}
+
+/// Beams (or timelines) leaving the manifold through each side or ending in an absorber.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Exits<C = usize> {
+    pub top: C,
+    pub left: C,
+    pub right: C,
+    pub bottom: C,
+    pub absorbed: C,
+}
+
+impl<C: TimelineCount> Exits<C> {
+    pub fn total(&self) -> Result<C, TraceError> {
+        let mut total = C::default();
+        for count in [
+            &self.top,
+            &self.left,
+            &self.right,
+            &self.bottom,
+            &self.absorbed,
+        ] {
+            add_to(&mut total, count)?;
+        }
+        Ok(total)
+    }
+
+    fn add(&mut self, exit: Exit, count: &C) -> Result<(), TraceError> {
+        let slot = match exit {
+            Exit::Top => &mut self.top,
+            Exit::Left => &mut self.left,
+            Exit::Right => &mut self.right,
+            Exit::Bottom => &mut self.bottom,
+            Exit::Absorbed => &mut self.absorbed,
+        };
+        add_to(slot, count)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TraceError {
+    /// A particle can come back to this cell moving the same way, so there are endless timelines.
+    Cycle { row: usize, col: usize },
+    /// There are more timelines than the count type can hold.
+    Overflow,
+}
+
+impl fmt::Display for TraceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TraceError::Cycle { row, col } => {
+                write!(f, "beams loop through row {row}, column {col}")
+            }
+            TraceError::Overflow => write!(f, "timeline count overflowed, use a wider count type"),
+        }
+    }
+}
+
+impl std::error::Error for TraceError {}
+
+/// A number of beams or timelines. Timelines double with every splitter row, so counting only
+/// ever adds, and has to say when the sum no longer fits.
+pub trait TimelineCount: Clone + Default + PartialEq + fmt::Debug {
+    fn one() -> Self;
+
+    /// `None` when the sum does not fit.
+    fn checked_add(&self, rhs: &Self) -> Option<Self>;
+
+    /// Approximate value, for shading heatmaps.
+    fn to_f64(&self) -> f64;
+
+    fn is_zero(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+macro_rules! fixed_width_count {
+    ($($t:ty),*) => {$(
+        impl TimelineCount for $t {
+            fn one() -> Self {
+                1
+            }
+
+            fn checked_add(&self, rhs: &Self) -> Option<Self> {
+                <$t>::checked_add(*self, *rhs)
+            }
+
+            fn to_f64(&self) -> f64 {
+                *self as f64
+            }
+        }
+    )*};
+}
+
+fixed_width_count!(u32, u64, u128, usize);
+
+/// Arbitrary precision count: little-endian base 2^32 limbs, without trailing zero limbs.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct BigCount(Vec<u32>);
+
+impl From<u64> for BigCount {
+    fn from(value: u64) -> Self {
+        let mut limbs = vec![value as u32, (value >> 32) as u32];
+        while limbs.last() == Some(&0) {
+            limbs.pop();
+        }
+        BigCount(limbs)
+    }
+}
+
+impl TimelineCount for BigCount {
+    fn one() -> Self {
+        BigCount(vec![1])
+    }
+
+    fn checked_add(&self, rhs: &Self) -> Option<Self> {
+        let (long, short) = if self.0.len() >= rhs.0.len() {
+            (&self.0, &rhs.0)
+        } else {
+            (&rhs.0, &self.0)
+        };
+
+        let mut limbs = Vec::with_capacity(long.len() + 1);
+        let mut carry = 0u64;
+        for (idx, &limb) in long.iter().enumerate() {
+            let sum = limb as u64 + short.get(idx).copied().unwrap_or(0) as u64 + carry;
+            limbs.push(sum as u32);
+            carry = sum >> 32;
+        }
+        if carry > 0 {
+            limbs.push(carry as u32);
+        }
+
+        Some(BigCount(limbs))
+    }
+
+    fn to_f64(&self) -> f64 {
+        self.0
+            .iter()
+            .rev()
+            .fold(0.0, |acc, &limb| acc * 4294967296.0 + limb as f64)
+    }
+}
+
+impl fmt::Display for BigCount {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // peel off base 10^9 chunks, least significant first
+        let mut limbs = self.0.clone();
+        let mut chunks = Vec::new();
+        while !limbs.is_empty() {
+            let mut rem = 0u64;
+            for limb in limbs.iter_mut().rev() {
+                let cur = (rem << 32) | *limb as u64;
+                *limb = (cur / 1_000_000_000) as u32;
+                rem = cur % 1_000_000_000;
+            }
+            chunks.push(rem as u32);
+            while limbs.last() == Some(&0) {
+                limbs.pop();
+            }
+        }
+
+        match chunks.split_last() {
+            None => write!(f, "0"),
+            Some((most, rest)) => {
+                write!(f, "{most}")?;
+                rest.iter()
+                    .rev()
+                    .try_for_each(|chunk| write!(f, "{chunk:09}"))
+            }
+        }
+    }
+}
+
+/// Count modulo `P`, for when only the answer mod a prime is wanted. Never overflows.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ModCount<const P: u64>(u64);
+
+impl<const P: u64> ModCount<P> {
+    pub fn value(&self) -> u64 {
+        self.0
+    }
+}
+
+impl<const P: u64> TimelineCount for ModCount<P> {
+    fn one() -> Self {
+        ModCount(1 % P)
+    }
+
+    fn checked_add(&self, rhs: &Self) -> Option<Self> {
+        Some(ModCount(
+            ((self.0 as u128 + rhs.0 as u128) % P as u128) as u64,
+        ))
+    }
+
+    fn to_f64(&self) -> f64 {
+        self.0 as f64
+    }
+}
+
+impl<const P: u64> fmt::Display for ModCount<P> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+fn add_to<C: TimelineCount>(slot: &mut C, count: &C) -> Result<(), TraceError> {
+    *slot = slot.checked_add(count).ok_or(TraceError::Overflow)?;
+    Ok(())
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Direction {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+impl Direction {
+    fn index(self) -> usize {
+        self as usize
+    }
+
+    fn reverse(self) -> Direction {
+        match self {
+            Direction::Up => Direction::Down,
+            Direction::Down => Direction::Up,
+            Direction::Left => Direction::Right,
+            Direction::Right => Direction::Left,
+        }
+    }
+
+    fn is_vertical(self) -> bool {
+        matches!(self, Direction::Up | Direction::Down)
+    }
+
+    /// Both sides of a beam moving this way.
+    fn sides(self) -> [Direction; 2] {
+        if self.is_vertical() {
+            [Direction::Left, Direction::Right]
+        } else {
+            [Direction::Up, Direction::Down]
+        }
+    }
+
+    /// Reflection on a `/` mirror.
+    fn slash(self) -> Direction {
+        match self {
+            Direction::Up => Direction::Right,
+            Direction::Right => Direction::Up,
+            Direction::Down => Direction::Left,
+            Direction::Left => Direction::Down,
+        }
+    }
+
+    /// Reflection on a `\` mirror.
+    fn backslash(self) -> Direction {
+        match self {
+            Direction::Up => Direction::Left,
+            Direction::Left => Direction::Up,
+            Direction::Down => Direction::Right,
+            Direction::Right => Direction::Down,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Exit {
+    Top,
+    Left,
+    Right,
+    Bottom,
+    Absorbed,
+}
+
+/// A beam in a cell whose element has already acted on it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Beam {
+    row: usize,
+    col: usize,
+    dir: Direction,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Outcome {
+    Beam(Beam),
+    Exit(Exit),
+}
+
+/// A cell beams moved into and whether a splitter split them there.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Step {
+    row: usize,
+    col: usize,
+    split: bool,
+}
+
+/// Cells entered by one `advance`: the one moved into, then those the halves of a `^` land in.
+type Steps = [Option<Step>; 3];
+
+/// Where a step from a cell ends up once the edge policy is applied.
+enum Offset {
+    Inside(usize, usize),
+    Bounced,
+    Outside(Exit),
+}
+
+impl Manifold {
+    fn cell(&self, row: usize, col: usize) -> u8 {
+        self.cells[row * self.width + col]
+    }
+
+    fn is_splitter(&self, row: usize, col: usize) -> bool {
+        self.cell(row, col) == b'^'
+    }
+
+    /// Only empty cells, sources and `^` splitters: beams never leave the downward sweep.
+    fn is_downward_only(&self) -> bool {
+        self.cells.iter().all(|c| matches!(c, b'.' | b'S' | b'^'))
+    }
+
+    fn state(&self, beam: Beam) -> usize {
+        (beam.row * self.width + beam.col) * 4 + beam.dir.index()
+    }
+
+    fn offset(&self, row: usize, col: usize, dir: Direction, edges: EdgePolicy) -> Offset {
+        match dir {
+            Direction::Up if row == 0 => Offset::Outside(Exit::Top),
+            Direction::Up => Offset::Inside(row - 1, col),
+            Direction::Down if row + 1 == self.height => Offset::Outside(Exit::Bottom),
+            Direction::Down => Offset::Inside(row + 1, col),
+            Direction::Left if col > 0 => Offset::Inside(row, col - 1),
+            Direction::Right if col + 1 < self.width => Offset::Inside(row, col + 1),
+            Direction::Left | Direction::Right => match edges {
+                EdgePolicy::Lost if dir == Direction::Left => Offset::Outside(Exit::Left),
+                EdgePolicy::Lost => Offset::Outside(Exit::Right),
+                EdgePolicy::Reflect => Offset::Bounced,
+                EdgePolicy::Wrap if dir == Direction::Left => Offset::Inside(row, self.width - 1),
+                EdgePolicy::Wrap => Offset::Inside(row, 0),
+            },
+        }
+    }
+
+    /// Moves `beam` one cell and lets the element there act on it.
+    fn advance(&self, beam: Beam, edges: EdgePolicy, out: &mut Vec<Outcome>) -> Steps {
+        let (row, col) = match self.offset(beam.row, beam.col, beam.dir, edges) {
+            Offset::Inside(row, col) => (row, col),
+            Offset::Bounced => {
+                out.push(Outcome::Beam(Beam {
+                    dir: beam.dir.reverse(),
+                    ..beam
+                }));
+                let (row, col) = (beam.row, beam.col);
+                return [
+                    Some(Step {
+                        row,
+                        col,
+                        split: false,
+                    }),
+                    None,
+                    None,
+                ];
+            }
+            Offset::Outside(exit) => {
+                out.push(Outcome::Exit(exit));
+                return [None; 3];
+            }
+        };
+
+        let dir = beam.dir;
+        if self.cell(row, col) != b'^' {
+            return [Some(self.act(row, col, dir, out)), None, None];
+        }
+
+        // the halves land next to the splitter and keep going the same way, meeting whatever is
+        // there; another `^` lets them by, they are already past its row
+        let mut steps = [
+            Some(Step {
+                row,
+                col,
+                split: true,
+            }),
+            None,
+            None,
+        ];
+        for (step, side) in steps[1..].iter_mut().zip(dir.sides()) {
+            *step = match self.offset(row, col, side, edges) {
+                Offset::Inside(row, col) if self.cell(row, col) == b'^' => {
+                    out.push(Outcome::Beam(Beam { row, col, dir }));
+                    Some(Step {
+                        row,
+                        col,
+                        split: false,
+                    })
+                }
+                Offset::Inside(row, col) => Some(self.act(row, col, dir, out)),
+                Offset::Bounced => {
+                    out.push(Outcome::Beam(Beam { row, col, dir }));
+                    None
+                }
+                Offset::Outside(exit) => {
+                    out.push(Outcome::Exit(exit));
+                    None
+                }
+            };
+        }
+
+        steps
+    }
+
+    /// Lets the element at `(row, col)` act on a beam that arrived there going `dir`; `^` is
+    /// handled by `advance`.
+    fn act(&self, row: usize, col: usize, dir: Direction, out: &mut Vec<Outcome>) -> Step {
+        let at = |dir| Outcome::Beam(Beam { row, col, dir });
+        let mut split = false;
+
+        match self.cell(row, col) {
+            b'#' => out.push(Outcome::Exit(Exit::Absorbed)),
+            b'/' => out.push(at(dir.slash())),
+            b'\\' => out.push(at(dir.backslash())),
+            b'|' if !dir.is_vertical() => {
+                out.extend([at(Direction::Up), at(Direction::Down)]);
+                split = true;
+            }
+            b'-' if dir.is_vertical() => {
+                out.extend([at(Direction::Left), at(Direction::Right)]);
+                split = true;
+            }
+            _ => out.push(at(dir)),
+        }
+
+        Step { row, col, split }
+    }
+
+    fn source_beams(&self) -> impl Iterator<Item = Beam> + '_ {
+        self.sources.iter().map(|&(row, col)| Beam {
+            row,
+            col,
+            dir: Direction::Down,
+        })
+    }
+}
+
+/// Sweeps the rows below the sources, moving a per-column beam count down one row at a time.
+///
+/// `on_enter(row, col, count, split)` is called whenever `count` beams enter a cell, `split`
+/// telling whether it is a splitter splitting them. Unless `quantum`, beams meeting in a cell
+/// merge into one instead of adding up.
+fn sweep<C: TimelineCount>(
+    manifold: &Manifold,
+    edges: EdgePolicy,
+    quantum: bool,
+    mut on_enter: impl FnMut(usize, usize, &C, bool) -> Result<(), TraceError>,
+) -> Result<Exits<C>, TraceError> {
+    let width = manifold.width;
+    let first_row = manifold.sources.first().map_or(manifold.height, |s| s.0);
+
+    let mut exits = Exits::default();
+    let mut beams = vec![C::default(); width];
+    let mut next = vec![C::default(); width];
+    let mut sources = manifold.sources.iter().peekable();
+
+    for row in first_row..manifold.height {
+        if row > first_row {
+            next.fill(C::default());
+
+            for (col, count) in beams.iter().enumerate() {
+                if count.is_zero() {
+                    continue;
+                }
+
+                if !manifold.is_splitter(row, col) {
+                    add_to(&mut next[col], count)?;
+                    on_enter(row, col, count, false)?;
+                    continue;
+                }
+
+                on_enter(row, col, count, true)?;
+
+                // left
+                let left = match (col.checked_sub(1), edges) {
+                    (Some(left), _) => Some(left),
+                    (None, EdgePolicy::Lost) => None,
+                    (None, EdgePolicy::Reflect) => Some(0),
+                    (None, EdgePolicy::Wrap) => Some(width - 1),
+                };
+
+                // right
+                let right = match (col + 1 < width, edges) {
+                    (true, _) => Some(col + 1),
+                    (false, EdgePolicy::Lost) => None,
+                    (false, EdgePolicy::Reflect) => Some(width - 1),
+                    (false, EdgePolicy::Wrap) => Some(0),
+                };
+
+                for (side, lost) in [(left, &mut exits.left), (right, &mut exits.right)] {
+                    match side {
+                        Some(side) => {
+                            add_to(&mut next[side], count)?;
+                            if side != col {
+                                on_enter(row, side, count, false)?;
+                            }
+                        }
+                        None => add_to(lost, count)?,
+                    }
+                }
+            }
+
+            std::mem::swap(&mut beams, &mut next);
+        }
+
+        while let Some(&(_, col)) = sources.next_if(|s| s.0 == row) {
+            add_to(&mut beams[col], &C::one())?;
+            on_enter(row, col, &C::one(), false)?;
+        }
+
+        if !quantum {
+            for count in beams.iter_mut().filter(|count| !count.is_zero()) {
+                *count = C::one();
+            }
+        }
+    }
+
+    for count in &beams {
+        add_to(&mut exits.bottom, count)?;
+    }
+    Ok(exits)
+}
+
+/// Follows every beam once, stopping where beams start repeating themselves.
+fn trace_splits(manifold: &Manifold, edges: EdgePolicy) -> (usize, Exits) {
+    let mut seen = vec![false; manifold.cells.len() * 4];
+    let mut split = vec![false; manifold.cells.len()];
+    let mut exits = Exits::default();
+
+    let mut queue: Vec<Beam> = manifold.source_beams().collect();
+    for &beam in &queue {
+        seen[manifold.state(beam)] = true;
+    }
+
+    let mut outcomes = Vec::new();
+    while let Some(beam) = queue.pop() {
+        outcomes.clear();
+        for step in manifold
+            .advance(beam, edges, &mut outcomes)
+            .iter()
+            .flatten()
+        {
+            if step.split {
+                split[step.row * manifold.width + step.col] = true;
+            }
+        }
+
+        for &outcome in &outcomes {
+            match outcome {
+                Outcome::Exit(exit) => exits
+                    .add(exit, &1)
+                    .expect("distinct beams are bounded by the manifold size"),
+                Outcome::Beam(next) => {
+                    let state = manifold.state(next);
+                    if !seen[state] {
+                        seen[state] = true;
+                        queue.push(next);
+                    }
+                }
+            }
+        }
+    }
+
+    (split.iter().filter(|&&s| s).count(), exits)
+}
+
+/// Every state reachable from the sources, ordered so that a state comes before those it leads to.
+///
+/// The reachable states form a graph; any cycle in it means endless timelines.
+fn topological_order(manifold: &Manifold, edges: EdgePolicy) -> Result<Vec<Beam>, TraceError> {
+    #[derive(Clone, Copy, PartialEq, Eq)]
+    enum Mark {
+        New,
+        Open,
+        Done,
+    }
+
+    let mut mark = vec![Mark::New; manifold.cells.len() * 4];
+    let mut post_order = Vec::new();
+
+    for source in manifold.source_beams() {
+        if mark[manifold.state(source)] != Mark::New {
+            continue;
+        }
+
+        // (beam, outcomes of its step, how many of them are explored)
+        let mut stack: Vec<(Beam, Vec<Outcome>, usize)> = Vec::new();
+        let open = |beam: Beam, mark: &mut [Mark]| {
+            mark[manifold.state(beam)] = Mark::Open;
+            let mut outcomes = Vec::new();
+            manifold.advance(beam, edges, &mut outcomes);
+            (beam, outcomes, 0)
+        };
+        stack.push(open(source, &mut mark));
+
+        while let Some((beam, outcomes, next)) = stack.last_mut() {
+            let Some(&outcome) = outcomes.get(*next) else {
+                mark[manifold.state(*beam)] = Mark::Done;
+                post_order.push(*beam);
+                stack.pop();
+                continue;
+            };
+            *next += 1;
+
+            let Outcome::Beam(child) = outcome else {
+                continue;
+            };
+
+            match mark[manifold.state(child)] {
+                Mark::Done => {}
+                Mark::Open => {
+                    return Err(TraceError::Cycle {
+                        row: child.row,
+                        col: child.col,
+                    });
+                }
+                Mark::New => stack.push(open(child, &mut mark)),
+            }
+        }
+    }
+
+    post_order.reverse();
+    Ok(post_order)
+}
+
+/// Pushes the timelines of every source forward through the states in topological order.
+fn trace_heatmap<C: TimelineCount>(
+    manifold: &Manifold,
+    edges: EdgePolicy,
+) -> Result<Heatmap<C>, TraceError> {
+    let order = topological_order(manifold, edges)?;
+
+    let mut heatmap = Heatmap::new(manifold);
+    let mut paths = vec![C::default(); manifold.cells.len() * 4];
+
+    for source in manifold.source_beams() {
+        add_to(&mut paths[manifold.state(source)], &C::one())?;
+        heatmap.enter(source.row, source.col, &C::one(), false)?;
+    }
+
+    let mut outcomes = Vec::new();
+    for beam in order {
+        let count = std::mem::take(&mut paths[manifold.state(beam)]);
+
+        outcomes.clear();
+        for step in manifold
+            .advance(beam, edges, &mut outcomes)
+            .iter()
+            .flatten()
+        {
+            heatmap.enter(step.row, step.col, &count, step.split)?;
+        }
+
+        for &outcome in &outcomes {
+            match outcome {
+                Outcome::Exit(exit) => heatmap.exits.add(exit, &count)?,
+                Outcome::Beam(child) => add_to(&mut paths[manifold.state(child)], &count)?,
+            }
+        }
+    }
+
+    Ok(heatmap)
+}
+
+/// Timelines entering every cell of the manifold and splitter hits, in quantum mode.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Heatmap<C = usize> {
+    width: usize,
+    height: usize,
+    timelines: Vec<C>,
+    splitter_hits: Vec<C>,
+    pub exits: Exits<C>,
+}
+
+impl<C: TimelineCount> Heatmap<C> {
+    fn new(manifold: &Manifold) -> Self {
+        Heatmap {
+            width: manifold.width,
+            height: manifold.height,
+            timelines: vec![C::default(); manifold.cells.len()],
+            splitter_hits: vec![C::default(); manifold.cells.len()],
+            exits: Exits::default(),
+        }
+    }
+
+    fn enter(&mut self, row: usize, col: usize, count: &C, split: bool) -> Result<(), TraceError> {
+        let idx = row * self.width + col;
+        add_to(&mut self.timelines[idx], count)?;
+        if split {
+            add_to(&mut self.splitter_hits[idx], count)?;
+        }
+        Ok(())
+    }
+
+    /// How many timelines enter the cell (a source counts as entering its own cell).
+    pub fn timelines(&self, row: usize, col: usize) -> &C {
+        &self.timelines[row * self.width + col]
+    }
+
+    /// How many timelines the splitter in this cell split, 0 for other cells.
+    pub fn splitter_hits(&self, row: usize, col: usize) -> &C {
+        &self.splitter_hits[row * self.width + col]
+    }
+
+    /// Cell intensities in `0.0..=1.0`. Counts grow exponentially with depth, so this is log scaled.
+    fn intensities(&self) -> impl Iterator<Item = f64> + '_ {
+        let max = self.timelines.iter().map(C::to_f64).fold(0.0, f64::max);
+        let scale = (max + 1.0).ln().max(f64::MIN_POSITIVE);
+
+        self.timelines
+            .iter()
+            .map(move |count| (count.to_f64() + 1.0).ln() / scale)
+    }
+
+    /// One character per cell, from ' ' (no timelines) to '@' (the busiest cell).
+    pub fn render_ascii(&self) -> String {
+        const RAMP: &[u8] = b" .:-=+*#%@";
+
+        let mut out = String::with_capacity((self.width + 1) * self.height);
+        for (idx, intensity) in self.intensities().enumerate() {
+            let level = (intensity * (RAMP.len() - 1) as f64).ceil() as usize;
+            out.push(RAMP[level.min(RAMP.len() - 1)] as char);
+
+            if (idx + 1) % self.width == 0 {
+                out.push('\n');
+            }
+        }
+        out
+    }
+
+    /// Plain (P2) PGM greyscale image, one pixel per cell, brighter for more timelines.
+    pub fn render_pgm(&self) -> String {
+        let mut out = format!("P2\n{} {}\n255\n", self.width, self.height);
+
+        for (idx, intensity) in self.intensities().enumerate() {
+            out.push_str(&((intensity * 255.0).round() as u8).to_string());
+            out.push(if (idx + 1) % self.width == 0 {
+                '\n'
+            } else {
+                ' '
+            });
+        }
+        out
+    }
+}
+
+/// Per-cell timeline counts and splitter hits, together with the timelines ending in each exit.
+pub fn timeline_heatmap<C: TimelineCount>(
+    manifold: &Manifold,
+    edges: EdgePolicy,
+) -> Result<Heatmap<C>, TraceError> {
+    if !manifold.is_downward_only() {
+        return trace_heatmap(manifold, edges);
+    }
+
+    let mut heatmap = Heatmap::new(manifold);
+    heatmap.exits = sweep(manifold, edges, true, |row, col, count, split| {
+        heatmap.enter(row, col, count, split)
+    })?;
+
+    Ok(heatmap)
+}
+
+/// Timelines ending in each exit; every split adds one to the ones the particles started in.
+///
+/// Fixed-width counts report `TraceError::Overflow` instead of wrapping; use `BigCount` for
+/// exact counts of any size or `ModCount` for counts modulo a prime.
+pub fn count_timelines<C: TimelineCount>(
+    manifold: &Manifold,
+    edges: EdgePolicy,
+) -> Result<Exits<C>, TraceError> {
+    if manifold.is_downward_only() {
+        return sweep(manifold, edges, true, |_, _, _, _| Ok(()));
+    }
+
+    trace_heatmap(manifold, edges).map(|heatmap| heatmap.exits)
+}
+
+/// Splitters reached by the classical beams, and how many beams end in each exit.
+pub fn count_splits(manifold: &Manifold, edges: EdgePolicy) -> (usize, Exits) {
+    if !manifold.is_downward_only() {
+        return trace_splits(manifold, edges);
+    }
+
+    // beams merge in a cell, so each splitter is reached at most once
+    let mut hitted_carets = 0;
+    let exits = sweep(manifold, edges, false, |_, _, _, split| {
+        hitted_carets += split as usize;
+        Ok(())
+    })
+    .expect("distinct beams are bounded by the manifold size");
+
+    (hitted_carets, exits)
+}
+
+pub fn parse_manifold(input: &str) -> Manifold {
+    // Skip empty lines that appear because of leading newline in raw string
+    let lines: Vec<&str> = input.lines().filter(|l| !l.trim().is_empty()).collect();
+
+    let width = lines.iter().map(|l| l.len()).max().unwrap_or(0);
+    let height = lines.len();
+
+    let mut cells = vec![b'.'; width * height];
+    let mut sources = Vec::new();
+
+    for (row, line) in lines.iter().enumerate() {
+        for (col, ch) in line.bytes().enumerate() {
+            assert!(
+                b".S^/\\|-#".contains(&ch),
+                "unknown manifold element {:?} at row {row}, column {col}",
+                ch as char
+            );
+
+            cells[row * width + col] = ch;
+            if ch == b'S' {
+                sources.push((row, col));
+            }
+        }
+    }
+
+    assert!(!sources.is_empty(), "manifold has no start 'S'");
+
+    Manifold {
+        cells,
+        width,
+        height,
+        sources,
+    }
+}
@@ -19,9 +19,28 @@
 
     What is the product of the x coordinates of the two junction boxes that if connect would close all circuits together.?
 
+    Closest pairs:
+
+    Materializing and sorting all n² / 2 pairs does not scale, so pairs are generated lazily in
+    increasing distance instead. A k-d tree answers "k nearest boxes to this one"; every box keeps a
+    cursor into its own neighbour list, and a heap holding each cursor's next pair hands out the
+    globally closest one. A cursor that runs out asks the tree again with twice the k, so memory
+    stays proportional to how far Kruskal actually has to go.
+
+    Coordinates are integers and strings are compared by their exact squared length, ties going to
+    the pair with the lower box indices, so "the first 1000 connections" is always the same set.
+
+    Metrics:
+
+    Boxes may have any number of coordinates (all the same), and the distance is pluggable:
+    Euclidean, Manhattan, Chebyshev, or any of them with per-axis weights. A metric only needs to
+    give an exact, monotone comparison key and a lower bound from a single axis, which is all the
+    k-d tree needs to prune.
 */
-use disjoint::DisjointSet;
-use std::collections::HashMap;
+use crate::sections::{SectionError, split_sections};
+use crate::unionfind::UnionFind;
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
 
 pub const INPUT: &str = include_str!("inputs/day08.txt");
 
@@ -47,95 +66,444 @@ pub const TEST_INPUT: &str = r#"162,817,812
 425,690,689
 "#;
 
-#[derive(Clone, Debug)]
+/// Coordinates are kept below `MAX_COORDINATE` in absolute value so squared distances fit a `u128`.
+pub const MAX_COORDINATE: i64 = 1 << 61;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct JunctionBox {
-    x: f64,
-    y: f64,
-    z: f64,
+    coords: Vec<i64>,
 }
 
 impl JunctionBox {
-    fn distance(&self, rhs: &JunctionBox) -> f64 {
-        let dx = rhs.x - self.x;
-        let dy = rhs.y - self.y;
-        let dz = rhs.z - self.z;
+    pub fn new(coords: Vec<i64>) -> Self {
+        JunctionBox { coords }
+    }
+
+    pub fn x(&self) -> i64 {
+        self.coords[0]
+    }
+
+    pub fn dimensions(&self) -> usize {
+        self.coords.len()
+    }
+
+    fn axis(&self, axis: usize) -> i64 {
+        self.coords[axis]
+    }
+}
+
+/// How far apart two boxes are, as an exact key that orders pairs like the real distance does.
+///
+/// Sums saturate instead of overflowing, which only matters for absurd weights.
+pub trait Metric: Clone {
+    /// Combines the absolute per-axis differences, given with their axis.
+    fn measure(&self, diffs: impl Iterator<Item = (usize, u64)>) -> u128;
+
+    /// Key of a real distance, so thresholds can be compared with `measure`.
+    fn key(&self, distance: u64) -> u128;
+
+    fn distance(&self, a: &JunctionBox, b: &JunctionBox) -> u128 {
+        self.measure(
+            a.coords
+                .iter()
+                .zip(&b.coords)
+                .map(|(a, b)| a.abs_diff(*b))
+                .enumerate(),
+        )
+    }
+
+    /// Lower bound for boxes `diff` apart on `axis`, whatever their other coordinates.
+    fn axis_bound(&self, axis: usize, diff: u64) -> u128 {
+        self.measure(std::iter::once((axis, diff)))
+    }
+}
+
+/// Straight line distance, keyed by its square.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Euclidean;
+
+/// Squared straight line distance; orders like `Euclidean` but thresholds are squared too.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SquaredEuclidean;
+
+/// Sum of the per-axis differences, for cables following rectilinear conduits.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Manhattan;
+
+/// Largest per-axis difference.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Chebyshev;
+
+/// Another metric over differences scaled by a weight per axis (missing weights count as 1).
+#[derive(Clone, Debug)]
+pub struct Weighted<M> {
+    pub weights: Vec<u64>,
+    pub metric: M,
+}
+
+fn square(diff: u64) -> u128 {
+    diff as u128 * diff as u128
+}
+
+impl Metric for Euclidean {
+    fn measure(&self, diffs: impl Iterator<Item = (usize, u64)>) -> u128 {
+        diffs.fold(0, |sum: u128, (_, diff)| sum.saturating_add(square(diff)))
+    }
+
+    fn key(&self, distance: u64) -> u128 {
+        square(distance)
+    }
+}
+
+impl Metric for SquaredEuclidean {
+    fn measure(&self, diffs: impl Iterator<Item = (usize, u64)>) -> u128 {
+        Euclidean.measure(diffs)
+    }
+
+    fn key(&self, distance: u64) -> u128 {
+        distance as u128
+    }
+}
+
+impl Metric for Manhattan {
+    fn measure(&self, diffs: impl Iterator<Item = (usize, u64)>) -> u128 {
+        diffs.fold(0, |sum: u128, (_, diff)| sum.saturating_add(diff as u128))
+    }
+
+    fn key(&self, distance: u64) -> u128 {
+        distance as u128
+    }
+}
+
+impl Metric for Chebyshev {
+    fn measure(&self, diffs: impl Iterator<Item = (usize, u64)>) -> u128 {
+        diffs.map(|(_, diff)| diff as u128).max().unwrap_or(0)
+    }
 
-        (dx * dx + dy * dy + dz * dz).sqrt()
+    fn key(&self, distance: u64) -> u128 {
+        distance as u128
     }
 }
 
-#[derive(Clone, Copy, Debug)]
+impl<M: Metric> Metric for Weighted<M> {
+    fn measure(&self, diffs: impl Iterator<Item = (usize, u64)>) -> u128 {
+        self.metric.measure(diffs.map(|(axis, diff)| {
+            let weight = self.weights.get(axis).copied().unwrap_or(1);
+            (axis, diff.saturating_mul(weight))
+        }))
+    }
+
+    fn key(&self, distance: u64) -> u128 {
+        self.metric.key(distance)
+    }
+}
+
+// Fields in this order so the derived ordering is by distance, ties broken by box indices.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
 struct LightString {
+    distance: u128, // the metric's key
     l_box: usize,
     r_box: usize,
-    distance: f64,
 }
 
-pub fn calculate_extension_size(junction_boxes: &[JunctionBox]) -> i64 {
-    let n = junction_boxes.len();
-    let edges = calculate_distances(junction_boxes);
+impl LightString {
+    fn new(l_box: usize, r_box: usize, distance: u128) -> Self {
+        LightString {
+            distance,
+            l_box,
+            r_box,
+        }
+    }
+
+    /// Same string with the lower box first, so both ends agree on where it sorts.
+    fn normalized(self) -> Self {
+        LightString::new(
+            self.l_box.min(self.r_box),
+            self.l_box.max(self.r_box),
+            self.distance,
+        )
+    }
+}
+
+/// Static k-d tree over the boxes: `order[lo..hi]` is a subtree rooted at its middle element,
+/// split on axis `depth % dimensions`.
+struct KdTree<'a, M> {
+    boxes: &'a [JunctionBox],
+    metric: &'a M,
+    dimensions: usize,
+    order: Vec<usize>,
+}
+
+impl<'a, M: Metric> KdTree<'a, M> {
+    fn new(boxes: &'a [JunctionBox], metric: &'a M) -> Self {
+        let dimensions = boxes.first().map_or(1, JunctionBox::dimensions);
+        let mut order: Vec<usize> = (0..boxes.len()).collect();
+        Self::build(boxes, dimensions, &mut order, 0);
+
+        KdTree {
+            boxes,
+            metric,
+            dimensions,
+            order,
+        }
+    }
+
+    fn build(boxes: &[JunctionBox], dimensions: usize, order: &mut [usize], depth: usize) {
+        if order.len() <= 1 {
+            return;
+        }
+
+        let mid = order.len() / 2;
+        let axis = depth % dimensions;
+        order.select_nth_unstable_by(mid, |&a, &b| boxes[a].axis(axis).cmp(&boxes[b].axis(axis)));
+
+        let (left, right) = order.split_at_mut(mid);
+        Self::build(boxes, dimensions, left, depth + 1);
+        Self::build(boxes, dimensions, &mut right[1..], depth + 1);
+    }
+
+    /// The `k` strings from box `from` to its closest boxes, closest first.
+    fn nearest(&self, from: usize, k: usize) -> Vec<LightString> {
+        let mut best = BinaryHeap::with_capacity(k + 1);
+        self.search(&self.order, 0, from, k, &mut best);
+        best.into_sorted_vec()
+    }
+
+    fn search(
+        &self,
+        order: &[usize],
+        depth: usize,
+        from: usize,
+        k: usize,
+        best: &mut BinaryHeap<LightString>,
+    ) {
+        if order.is_empty() || k == 0 {
+            return;
+        }
+
+        let mid = order.len() / 2;
+        let axis = depth % self.dimensions;
+        let node = order[mid];
+        let target = &self.boxes[from];
+
+        if node != from {
+            let distance = self.metric.distance(target, &self.boxes[node]);
+            let string = LightString::new(from, node, distance);
+            if best.len() < k {
+                best.push(string);
+            } else if string < *best.peek().unwrap() {
+                best.pop();
+                best.push(string);
+            }
+        }
+
+        let (here, split) = (target.axis(axis), self.boxes[node].axis(axis));
+        let (near, far) = if here < split {
+            (&order[..mid], &order[mid + 1..])
+        } else {
+            (&order[mid + 1..], &order[..mid])
+        };
+
+        self.search(near, depth + 1, from, k, best);
+        // `<=` so boxes tied with the current worst still get a chance on the far side
+        let bound = self.metric.axis_bound(axis, here.abs_diff(split));
+        if best.len() < k || bound <= best.peek().unwrap().distance {
+            self.search(far, depth + 1, from, k, best);
+        }
+    }
+}
+
+/// A box's nearest neighbours fetched so far, and how many of them were handed out.
+struct Neighbours {
+    found: Vec<LightString>,
+    next: usize,
+}
+
+/// Every pair of boxes exactly once, closest first.
+struct ClosestPairs<'a, M> {
+    tree: KdTree<'a, M>,
+    neighbours: Vec<Neighbours>,
+    queue: BinaryHeap<Reverse<(LightString, usize)>>, // (normalized string, box whose cursor it is)
+}
+
+impl<'a, M: Metric> ClosestPairs<'a, M> {
+    const FIRST_FETCH: usize = 8;
+
+    fn new(junction_boxes: &'a [JunctionBox], metric: &'a M) -> Self {
+        let mut pairs = ClosestPairs {
+            tree: KdTree::new(junction_boxes, metric),
+            neighbours: Vec::with_capacity(junction_boxes.len()),
+            queue: BinaryHeap::with_capacity(junction_boxes.len()),
+        };
+
+        for from in 0..junction_boxes.len() {
+            pairs.neighbours.push(Neighbours {
+                found: Vec::new(),
+                next: 0,
+            });
+            pairs.advance(from);
+        }
+
+        pairs
+    }
+
+    /// Queues the next closest string from box `from`, fetching more neighbours when needed.
+    fn advance(&mut self, from: usize) {
+        let others = self.tree.boxes.len() - 1;
+        let cursor = &mut self.neighbours[from];
+
+        if cursor.next == cursor.found.len() {
+            if cursor.found.len() == others {
+                return;
+            }
+
+            // neighbours are fully ordered, so a bigger fetch starts with the ones we already had
+            let k = (cursor.found.len() * 2).clamp(Self::FIRST_FETCH, others.max(1));
+            cursor.found = self.tree.nearest(from, k);
+        }
+
+        let string = cursor.found[cursor.next].normalized();
+        cursor.next += 1;
+        self.queue.push(Reverse((string, from)));
+    }
+}
+
+impl<M: Metric> Iterator for ClosestPairs<'_, M> {
+    type Item = LightString;
 
-    let mut uf = DisjointSet::with_len(n);
+    fn next(&mut self) -> Option<LightString> {
+        loop {
+            let Reverse((string, from)) = self.queue.pop()?;
+            self.advance(from);
 
-    let mut components = n;
+            // both ends find every string; only the lower box's copy is handed out
+            if from == string.l_box {
+                return Some(string);
+            }
+        }
+    }
+}
 
+/// Streams pairs into the union-find until one circuit is left, keeping only the last join;
+/// `connection_timeline` would record every pair on the way.
+pub fn calculate_extension_size(junction_boxes: &[JunctionBox], metric: &impl Metric) -> i128 {
+    let mut uf = UnionFind::new(junction_boxes.len());
     let mut last_join: Option<(usize, usize)> = None;
 
-    for e in edges {
-        if components == 1 {
+    for e in ClosestPairs::new(junction_boxes, metric) {
+        if uf.component_count() == 1 {
             break;
         }
 
-        if !uf.is_joined(e.l_box, e.r_box) {
-            uf.join(e.l_box, e.r_box);
-            components -= 1;
+        if uf.union(e.l_box, e.r_box) {
             last_join = Some((e.l_box, e.r_box));
         }
     }
 
     let (l, r) = last_join.expect("no merging edge found (graph may be disconnected)");
-    // Change the type/cast here to match your coordinate type.
-    // If x is i32/i64/usize, adjust accordingly.
-    let x1: i64 = junction_boxes[l].x as i64;
-    let x2: i64 = junction_boxes[r].x as i64;
-
-    x1 * x2
+    junction_boxes[l].x() as i128 * junction_boxes[r].x() as i128
 }
 
 /// Add shortest edges, skipping those that would connect vertices already in the same component.
-/// Returns the chosen edges (if you want to “mark joined pairs”) and a map root->component_size.
-fn connect_circuits(junction_boxes: &[JunctionBox], edges_to_add: usize) -> Vec<usize> {
-    let n = junction_boxes.len();
-    let edges = calculate_distances(junction_boxes);
+/// Returns the sizes of the resulting circuits, smallest first.
+fn connect_circuits(
+    junction_boxes: &[JunctionBox],
+    metric: &impl Metric,
+    edges_to_add: usize,
+) -> Vec<usize> {
+    let mut uf = UnionFind::new(junction_boxes.len());
+
+    for e in ClosestPairs::new(junction_boxes, metric).take(edges_to_add) {
+        uf.union(e.l_box, e.r_box);
+    }
 
-    let mut uf = DisjointSet::with_len(n); // :contentReference[oaicite:1]{index=1}
+    let mut sizes: Vec<usize> = uf.component_sizes().collect();
+    sizes.sort_unstable();
+    sizes
+}
 
-    for idx in 0..edges_to_add {
-        let e = edges[idx];
+/// State of the circuits right after one pair of boxes was processed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ConnectionStep {
+    pub l_box: usize,
+    pub r_box: usize,
+    /// The metric's key, e.g. the squared distance for `Euclidean`.
+    pub distance: u128,
+    /// Both boxes were already in the same circuit, so nothing changed.
+    pub redundant: bool,
+    pub circuits: usize,
+    pub largest: usize,
+}
 
-        if !uf.is_joined(e.l_box, e.r_box) {
-            uf.join(e.l_box, e.r_box);
-        }
+/// Kruskal replayed pair by pair, closest first.
+#[derive(Clone, Debug)]
+pub struct ConnectionTimeline<M> {
+    boxes: usize,
+    metric: M,
+    steps: Vec<ConnectionStep>,
+}
+
+impl<M: Metric> ConnectionTimeline<M> {
+    pub fn steps(&self) -> &[ConnectionStep] {
+        &self.steps
     }
 
-    // Compute component sizes (root->size) by querying representatives.
-    // DisjointSet doesn't store sizes for you, so we count.
-    let mut size_by_root: HashMap<usize, usize> = HashMap::new();
-    for v in 0..n {
-        // DisjointSet exposes a representative; in this crate it’s called `root` / representative.
-        // In 0.8, you can use `uf.find(v)` via the method the crate provides.
-        // We'll use `uf.root(v)` pattern defensively:
-        let r = uf.root_of(v); // see note below
-        *size_by_root.entry(r).or_insert(0) += 1;
+    /// State after the first `pairs` pairs, `None` for 0 or past the end of the timeline.
+    pub fn after_pairs(&self, pairs: usize) -> Option<&ConnectionStep> {
+        pairs.checked_sub(1).and_then(|idx| self.steps.get(idx))
     }
 
-    let mut sizes: Vec<usize> = size_by_root.into_values().collect();
-    sizes.sort_unstable();
-    sizes
+    /// State once every pair at most `max_distance` apart was processed, `None` if there are none.
+    pub fn within_distance(&self, max_distance: u64) -> Option<&ConnectionStep> {
+        let max = self.metric.key(max_distance);
+        let pairs = self.steps.partition_point(|step| step.distance <= max);
+        self.after_pairs(pairs)
+    }
+
+    /// The join that merged the last two circuits seen, if any pair joined anything.
+    pub fn last_join(&self) -> Option<&ConnectionStep> {
+        self.steps.iter().rev().find(|step| !step.redundant)
+    }
+
+    pub fn fully_connected(&self) -> bool {
+        self.boxes <= 1 || self.steps.last().is_some_and(|step| step.circuits == 1)
+    }
+}
+
+/// Processes up to `max_pairs` pairs, stopping early once every box is in a single circuit.
+pub fn connection_timeline<M: Metric>(
+    junction_boxes: &[JunctionBox],
+    metric: &M,
+    max_pairs: usize,
+) -> ConnectionTimeline<M> {
+    let mut uf = UnionFind::new(junction_boxes.len());
+    let mut steps = Vec::new();
+
+    for e in ClosestPairs::new(junction_boxes, metric).take(max_pairs) {
+        if uf.component_count() == 1 {
+            break;
+        }
+
+        let redundant = !uf.union(e.l_box, e.r_box);
+        steps.push(ConnectionStep {
+            l_box: e.l_box,
+            r_box: e.r_box,
+            distance: e.distance,
+            redundant,
+            circuits: uf.component_count(),
+            largest: uf.largest_size(),
+        });
+    }
+
+    ConnectionTimeline {
+        boxes: junction_boxes.len(),
+        metric: metric.clone(),
+        steps,
+    }
 }
 
-pub fn calculate_circuits(junction_boxes: &Vec<JunctionBox>, n: usize) -> usize {
-    let mut sizes = connect_circuits(junction_boxes, n);
+pub fn calculate_circuits(junction_boxes: &[JunctionBox], metric: &impl Metric, n: usize) -> usize {
+    let mut sizes = connect_circuits(junction_boxes, metric, n);
 
     let first = sizes.pop().unwrap();
     let second = sizes.pop().unwrap();
@@ -144,37 +512,140 @@ pub fn calculate_circuits(junction_boxes: &Vec<JunctionBox>, n: usize) -> usize
     first * second * third
 }
 
-fn calculate_distances(junction_boxes: &[JunctionBox]) -> Vec<LightString> {
-    let n = junction_boxes.len();
+fn parse_junction_box(line: &str) -> Result<JunctionBox, String> {
+    let coordinates = line
+        .split(',')
+        .map(|part| {
+            let value: i64 = part
+                .trim()
+                .parse()
+                .map_err(|err| format!("bad coordinate {part:?}: {err}"))?;
+            if value.abs() >= MAX_COORDINATE {
+                return Err(format!("coordinate {value} is out of range"));
+            }
+            Ok(value)
+        })
+        .collect::<Result<Vec<_>, _>>()?;
 
-    let mut result = Vec::new();
-    for i in 0..junction_boxes.len() {
-        for j in (i + 1)..n {
-            let d = junction_boxes[i].distance(&junction_boxes[j]);
-            result.push(LightString {
-                l_box: i,
-                r_box: j,
-                distance: d,
-            });
+    Ok(JunctionBox::new(coordinates))
+}
+
+/// One box per line as comma-separated coordinates (`x,y,z` for the puzzle); blank lines are
+/// ignored. Every box must have as many coordinates as the first one.
+pub fn parse_locations(input: &str) -> Result<Vec<JunctionBox>, SectionError> {
+    let mut junction_boxes: Vec<JunctionBox> = Vec::new();
+
+    for mut section in split_sections(input) {
+        section.name = "junction boxes".to_string();
+        for ((line_no, line), junction_box) in section
+            .lines()
+            .zip(section.parse_lines(parse_junction_box)?)
+        {
+            if let Some(first) = junction_boxes.first()
+                && first.dimensions() != junction_box.dimensions()
+            {
+                return Err(section.error(
+                    line_no,
+                    format!(
+                        "{line:?}: expected {} coordinates like the first box, found {}",
+                        first.dimensions(),
+                        junction_box.dimensions()
+                    ),
+                ));
+            }
+            junction_boxes.push(junction_box);
         }
     }
 
-    result.sort_unstable_by(|s1, s2| s1.distance.partial_cmp(&s2.distance).unwrap());
-    result
+    Ok(junction_boxes)
 }
 
-pub fn parse_locations(input: &str) -> Vec<JunctionBox> {
-    input
-        .lines()
-        .filter(|line| !line.trim().is_empty())
-        .map(|line| {
-            let mut parts = line.split(',');
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lcg::Lcg;
 
-            let x = parts.next().unwrap().parse().unwrap();
-            let y = parts.next().unwrap().parse().unwrap();
-            let z = parts.next().unwrap().parse().unwrap();
+    fn all_pairs_sorted(junction_boxes: &[JunctionBox], metric: &impl Metric) -> Vec<LightString> {
+        let n = junction_boxes.len();
 
-            JunctionBox { x, y, z }
-        })
-        .collect()
+        let mut result = Vec::new();
+        for i in 0..n {
+            for j in (i + 1)..n {
+                let d = metric.distance(&junction_boxes[i], &junction_boxes[j]);
+                result.push(LightString::new(i, j, d));
+            }
+        }
+
+        result.sort_unstable();
+        result
+    }
+
+    fn random_boxes(count: usize, dimensions: usize) -> Vec<JunctionBox> {
+        let mut rng = Lcg::new(7);
+        let mut next = || rng.below(12) as i64 - 6; // a tiny grid, so plenty of ties and duplicate boxes
+
+        (0..count)
+            .map(|_| JunctionBox::new((0..dimensions).map(|_| next()).collect()))
+            .collect()
+    }
+
+    fn assert_matches_sorting(junction_boxes: &[JunctionBox], metric: &impl Metric) {
+        let lazy: Vec<_> = ClosestPairs::new(junction_boxes, metric).collect();
+        assert_eq!(lazy, all_pairs_sorted(junction_boxes, metric));
+    }
+
+    #[test]
+    fn test_closest_pairs_match_sorting_every_pair() {
+        assert_matches_sorting(&random_boxes(120, 3), &Euclidean);
+
+        let example = parse_locations(TEST_INPUT).unwrap();
+        assert_matches_sorting(&example, &Euclidean);
+    }
+
+    #[test]
+    fn test_closest_pairs_for_every_metric_and_dimension() {
+        let weighted = Weighted {
+            weights: vec![3, 1, 2],
+            metric: Manhattan,
+        };
+
+        for dimensions in 1..=4 {
+            let junction_boxes = random_boxes(60, dimensions);
+            assert_matches_sorting(&junction_boxes, &SquaredEuclidean);
+            assert_matches_sorting(&junction_boxes, &Manhattan);
+            assert_matches_sorting(&junction_boxes, &Chebyshev);
+            assert_matches_sorting(&junction_boxes, &weighted);
+        }
+
+        let a = JunctionBox::new(vec![1, 2, 3]);
+        let b = JunctionBox::new(vec![4, -2, 3]);
+        assert_eq!(Euclidean.distance(&a, &b), 25);
+        assert_eq!(Manhattan.distance(&a, &b), 7);
+        assert_eq!(Chebyshev.distance(&a, &b), 4);
+        assert_eq!(weighted.distance(&a, &b), 13);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let err = parse_locations("1,2,3\n\n4,5.5,6\n").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "section `junction boxes`, line 3: \"4,5.5,6\": bad coordinate \"5.5\": invalid digit found in string"
+        );
+
+        let err = parse_locations("1,2,3\n4,5\n").unwrap_err();
+        assert_eq!(
+            (err.line, err.message.as_str()),
+            (
+                2,
+                "\"4,5\": expected 3 coordinates like the first box, found 2"
+            )
+        );
+
+        let err = parse_locations("1,2,3000000000000000000\n").unwrap_err();
+        assert_eq!(err.line, 1);
+
+        let planar = parse_locations("1,2\n3,4\n").unwrap();
+        assert_eq!(planar[1].dimensions(), 2);
+    }
 }
@@ -11,8 +11,20 @@
     Part two:
 
     what is the largest area of any rectangle you can make using only red and green tiles?
+    Consecutive red tiles (wrapping around) are joined by straight lines of green tiles, and
+    every tile inside that loop is green too.
+
+    The red tiles are the vertices of a rectilinear polygon from `geometry`, which answers
+    whether a rectangle is fully inside it without rasterizing the hundreds of thousands wide floor.
 */
-pub const INPUT: &str = include_str!("inputs/day08.txt");
+use crate::geometry::{Point, Polygon, PolygonError, Rectangle};
+use crate::sections::{SectionError, split_sections};
+use std::fmt;
+
+/// Most tiles, margin included, an ascii drawing of the floor goes up to.
+pub const MAX_ASCII_TILES: u64 = 1_000_000;
+
+pub const INPUT: &str = include_str!("inputs/day09.txt");
 
 pub const TEST_INPUT: &str = r#"7,1
 11,1
@@ -24,63 +36,321 @@ pub const TEST_INPUT: &str = r#"7,1
 7,3
 "#;
 
-#[derive(Clone, Debug)]
-pub struct Location {
-    x: u32,
-    y: u32,
-}
+/// A red tile.
+pub type Location = Point;
 
-impl Location {
-    fn diff(&self, rhs: &Location) -> Location {
-        Location {
-            x: self.x.abs_diff(rhs.x),
-            y: self.y.abs_diff(rhs.y),
+/// Tiles not dominated towards `corner`: no other tile is at least as far in both directions.
+///
+/// `corner` is the direction as signs, e.g. `(-1, -1)` for the lower left staircase.
+fn staircase(tiles: &[Location], corner: (i64, i64)) -> Vec<Location> {
+    let mut sorted: Vec<(i64, i64)> = tiles
+        .iter()
+        .map(|tile| (tile.x * corner.0, tile.y * corner.1))
+        .collect();
+    sorted.sort_unstable_by(|a, b| b.cmp(a));
+
+    // walking from the most extreme x, a tile is kept when it beats every y seen so far
+    let mut stairs = Vec::new();
+    let mut best_y = i64::MIN;
+    for (x, y) in sorted {
+        if y > best_y {
+            best_y = y;
+            stairs.push(Location::new(x * corner.0, y * corner.1));
         }
     }
 
-    fn rectangle_size(&self, rhs: &Location) -> u64 {
-        let diff = self.diff(rhs);
+    stairs
+}
 
-        let dx = diff.x as u64 + 1;
-        let dy = diff.y as u64 + 1;
+/// Two red tiles used as opposite corners.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Corners {
+    pub a: Location,
+    pub b: Location,
+}
 
-        dx * dy
+impl Corners {
+    pub fn rectangle(&self) -> Rectangle {
+        Rectangle::from_corners(self.a, self.b)
     }
+
+    pub fn tiles(&self) -> u64 {
+        self.rectangle().tiles()
+    }
+}
+
+/// Largest rectangle with two red tiles as opposite corners, `None` with fewer than two tiles.
+///
+/// A rectangle's corners can always be pushed out to tiles on the staircase facing their own
+/// direction without shrinking it, so only lower left against upper right and upper left against
+/// lower right staircase tiles are paired. Tiles on a loop mostly sit on some staircase, so
+/// pairing is done by `best_rising` in O(k log k) rather than trying all k^2 pairs.
+pub fn largest_area(input: &[Location]) -> Option<Corners> {
+    if input.len() < 2 {
+        return None;
+    }
+
+    // upper left against lower right is lower left against upper right upside down
+    let flip = |tile: &Location| Location::new(tile.x, -tile.y);
+    let flipped: Vec<Location> = input.iter().map(flip).collect();
+
+    let rising = best_rising(input);
+    let falling = best_rising(&flipped).map(|Corners { a, b }| Corners {
+        a: flip(&a),
+        b: flip(&b),
+    });
+
+    rising.into_iter().chain(falling).max_by_key(Corners::tiles)
 }
 
-pub fn largest_area(input: &[Location]) -> u64 {
-    let n = input.len();
+/// Largest rectangle with a lower left staircase tile `a` and an upper right one `b`.
+///
+/// Along the lower left staircase (x ascending, y descending) the best partner on the upper
+/// right one never moves back, so the partner of the middle tile splits the search: tiles before
+/// it only look at partners up to that one, tiles after it at partners from it on. Pairs with `b`
+/// strictly below and left of `a` are skipped, their area has the wrong sign; pairs with `b`
+/// beside or below `a` only come out as non-positive here and are found the other way round.
+fn best_rising(tiles: &[Location]) -> Option<Corners> {
+    let lower = staircase(tiles, (-1, -1));
+    let mut upper = staircase(tiles, (1, 1));
+    upper.reverse(); // x ascending, like `lower`
 
-    let mut max = 0;
-    for idx in 0..(n - 1) {
-        for jdx in idx..n {
-            let lhs = &input[idx];
-            let rhs = &input[jdx];
+    let area = |a: Location, b: Location| {
+        let (dx, dy) = (b.x as i128 - a.x as i128, b.y as i128 - a.y as i128);
+        if dx < 0 && dy < 0 {
+            None
+        } else {
+            Some((dx + 1) * (dy + 1))
+        }
+    };
 
-            let current_size = lhs.rectangle_size(&rhs);
+    let mut best: Option<(i128, Corners)> = None;
+    // (lower tiles, partner range) still to search
+    let mut pending = vec![(0..lower.len(), 0..upper.len())];
+    while let Some((tiles, partners)) = pending.pop() {
+        if tiles.is_empty() {
+            continue;
+        }
+        let mid = tiles.start + tiles.len() / 2;
+        let a = lower[mid];
 
-            if current_size > max {
-                max = current_size;
+        let mut partner = partners.start;
+        let mut mid_best = None;
+        for idx in partners.clone() {
+            if let Some(size) = area(a, upper[idx])
+                && mid_best.is_none_or(|best| size > best)
+            {
+                mid_best = Some(size);
+                partner = idx;
             }
         }
+
+        if let Some(size) = mid_best
+            && best.is_none_or(|(best, _)| size > best)
+        {
+            best = Some((
+                size,
+                Corners {
+                    a,
+                    b: upper[partner],
+                },
+            ));
+        }
+
+        pending.push((tiles.start..mid, partners.start..partner + 1));
+        pending.push((mid + 1..tiles.end, partner..partners.end));
     }
 
-    max
+    best.filter(|&(size, _)| size > 0)
+        .map(|(_, corners)| corners)
 }
 
-pub fn parse_locations(input: &str) -> Vec<Location> {
-    input
-        .lines()
-        .filter(|line| !line.trim().is_empty())
-        .map(|line| {
-            let (x, y) = line
-                .split_once(',')
-                .expect("invalid location format, expected `x,y`");
-
-            Location {
-                x: x.trim().parse::<u32>().expect("invalid x coordinate"),
-                y: y.trim().parse::<u32>().expect("invalid y coordinate"),
+/// Largest rectangle with red opposite corners made only of red and green tiles.
+pub fn largest_inside_area(red_tiles: &[Location]) -> Result<Option<Corners>, PolygonError> {
+    let floor = Polygon::new(red_tiles.to_vec())?;
+
+    let mut best: Option<Corners> = None;
+    for (idx, &a) in red_tiles.iter().enumerate() {
+        for &b in &red_tiles[idx + 1..] {
+            let corners = Corners { a, b };
+            let current_size = corners.tiles();
+
+            if best.is_none_or(|best| current_size > best.tiles())
+                && floor.contains_rectangle(&corners.rectangle())
+            {
+                best = Some(corners);
             }
+        }
+    }
+
+    Ok(best)
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RenderError {
+    /// The floor, margin included, has more than `MAX_ASCII_TILES` tiles.
+    TooLarge { width: u64, height: u64 },
+}
+
+impl fmt::Display for RenderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RenderError::TooLarge { width, height } => write!(
+                f,
+                "a {width}x{height} floor is too large to draw as ascii, at most {MAX_ASCII_TILES} tiles"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for RenderError {}
+
+/// Draws the floor for small inputs: `#` red tiles, `X` green ones, `O` the rest of the chosen
+/// rectangle, `.` everything else, with a one tile margin.
+///
+/// Green tiles are only drawn when the red tiles make a valid loop. Floors of more than
+/// `MAX_ASCII_TILES` tiles are refused before anything is drawn.
+pub fn render_ascii(
+    red_tiles: &[Location],
+    chosen: Option<&Corners>,
+) -> Result<String, RenderError> {
+    let Some(bounds) = bounding_box(red_tiles) else {
+        return Ok(String::new());
+    };
+    let (width, height) = (
+        bounds.width().saturating_add(2),
+        bounds.height().saturating_add(2),
+    );
+    if width.saturating_mul(height) > MAX_ASCII_TILES {
+        return Err(RenderError::TooLarge { width, height });
+    }
+    let floor = Polygon::new(red_tiles.to_vec()).ok();
+    let red: std::collections::HashSet<Location> = red_tiles.iter().copied().collect();
+
+    let mut out = String::new();
+    for y in bounds.min.y - 1..=bounds.max.y + 1 {
+        for x in bounds.min.x - 1..=bounds.max.x + 1 {
+            let tile = Location::new(x, y);
+            let in_chosen = chosen.is_some_and(|corners| {
+                let rectangle = corners.rectangle();
+                (rectangle.min.x..=rectangle.max.x).contains(&x)
+                    && (rectangle.min.y..=rectangle.max.y).contains(&y)
+            });
+
+            out.push(if red.contains(&tile) {
+                '#'
+            } else if in_chosen {
+                'O'
+            } else if floor.as_ref().is_some_and(|floor| floor.contains(tile)) {
+                'X'
+            } else {
+                '.'
+            });
+        }
+        out.push('\n');
+    }
+
+    Ok(out)
+}
+
+/// Draws the loop of red tiles (filled green), the red tiles themselves and the chosen rectangle
+/// as an SVG scaled to fit `size` pixels.
+pub fn render_svg(red_tiles: &[Location], chosen: Option<&Corners>, size: f64) -> String {
+    let Some(bounds) = bounding_box(red_tiles) else {
+        return format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{size}\" height=\"{size}\"/>\n"
+        );
+    };
+
+    // tiles are unit squares, so the floor spans one more than the coordinate range
+    let span = bounds.width().max(bounds.height()) as f64;
+    let scale = size / span;
+    let at = |x: i64, y: i64| {
+        (
+            (x - bounds.min.x) as f64 * scale,
+            (y - bounds.min.y) as f64 * scale,
+        )
+    };
+    let centre = |tile: &Location| {
+        let (x, y) = at(tile.x, tile.y);
+        (x + scale / 2.0, y + scale / 2.0)
+    };
+
+    let mut out = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w:.2}\" height=\"{h:.2}\" viewBox=\"0 0 {w:.2} {h:.2}\">\n",
+        w = bounds.width() as f64 * scale,
+        h = bounds.height() as f64 * scale,
+    );
+
+    let points: Vec<String> = red_tiles
+        .iter()
+        .map(|tile| {
+            let (x, y) = centre(tile);
+            format!("{x:.2},{y:.2}")
         })
-        .collect()
+        .collect();
+    out += &format!(
+        "  <polygon points=\"{}\" fill=\"#2e8b57\" fill-opacity=\"0.5\" stroke=\"#2e8b57\" stroke-width=\"{:.2}\"/>\n",
+        points.join(" "),
+        scale.max(1.0),
+    );
+
+    if let Some(corners) = chosen {
+        let rectangle = corners.rectangle();
+        let (x, y) = at(rectangle.min.x, rectangle.min.y);
+        out += &format!(
+            "  <rect x=\"{x:.2}\" y=\"{y:.2}\" width=\"{:.2}\" height=\"{:.2}\" fill=\"#1e90ff\" fill-opacity=\"0.4\" stroke=\"#1e90ff\"/>\n",
+            rectangle.width() as f64 * scale,
+            rectangle.height() as f64 * scale,
+        );
+    }
+
+    let radius = (scale / 2.0).max(1.5);
+    for tile in red_tiles {
+        let (x, y) = centre(tile);
+        out +=
+            &format!("  <circle cx=\"{x:.2}\" cy=\"{y:.2}\" r=\"{radius:.2}\" fill=\"#d22\"/>\n");
+    }
+
+    out += "</svg>\n";
+    out
+}
+
+fn bounding_box(tiles: &[Location]) -> Option<Rectangle> {
+    let first = *tiles.first()?;
+    Some(
+        tiles
+            .iter()
+            .fold(Rectangle::from_corners(first, first), |bounds, tile| {
+                Rectangle::from_corners(
+                    Location::new(bounds.min.x.min(tile.x), bounds.min.y.min(tile.y)),
+                    Location::new(bounds.max.x.max(tile.x), bounds.max.y.max(tile.y)),
+                )
+            }),
+    )
+}
+
+fn parse_location(line: &str) -> Result<Location, String> {
+    let (x, y) = line
+        .split_once(',')
+        .ok_or_else(|| "expected `x,y`".to_string())?;
+    let coordinate = |part: &str| {
+        part.trim()
+            .parse::<i64>()
+            .map_err(|err| format!("bad coordinate {part:?}: {err}"))
+    };
+
+    Ok(Location::new(coordinate(x)?, coordinate(y)?))
+}
+
+/// One red tile per line as `x,y`; blank lines are ignored.
+pub fn parse_locations(input: &str) -> Result<Vec<Location>, SectionError> {
+    let mut red_tiles = Vec::new();
+
+    for mut section in split_sections(input) {
+        section.name = "red tiles".to_string();
+        red_tiles.extend(section.parse_lines(parse_location)?);
+    }
+
+    Ok(red_tiles)
 }
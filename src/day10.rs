@@ -14,9 +14,35 @@
 
     Part two:
 
+    The buttons now add 1 to each counter they list, the counters start at 0 and have to reach the
+    joltage requirements exactly. What is the fewest button presses required for all the machines?
 
+    Solving the lights:
 
+    Pressing a button twice undoes it, so each button is pressed 0 or 1 times, and a light ends up
+    on when an odd number of its buttons were pressed: a linear system over GF(2) with one
+    equation per light and one unknown per button. Row reducing it gives one solution, or shows
+    there is none, and every other solution differs from it by a combination of the null space
+    basis (one vector per free button). Only those 2^free combinations are enumerated, in Gray code
+    order so each step toggles a single basis vector, to find the one pressing fewest buttons.
+
+    The same reduction answers more about a machine (`analyze`): the rank of the button matrix,
+    which buttons are redundant (every button appearing in a null space vector can be replaced by
+    the others in it), and how many of the solutions tie for fewest buttons.
+
+    Solving the joltage:
+
+    Now each button is pressed any number of times: A x = joltage over the non-negative integers,
+    minimizing the presses, an integer linear program handed to `ilp`. A button can be pressed at
+    most as often as the smallest requirement of the counters it feeds, which bounds the few free
+    buttons left after elimination.
 */
+use crate::ilp::Problem;
+use crate::sections::{SectionError, split_sections};
+use std::fmt;
+
+/// Most free buttons whose 2^free combinations are still enumerated, about a billion of them.
+pub const MAX_FREE_BUTTONS: usize = 30;
 
 pub const INPUT: &str = include_str!("inputs/day10.txt");
 
@@ -29,135 +55,443 @@ pub const TEST_INPUT: &str = r#"[.##.] (3) (1,3) (2) (2,3) (0,2) (0,1) {3,5,4,7}
 pub struct Line {
     pub lights: Vec<bool>,        // '.' false, '#' true
     pub buttons: Vec<Vec<usize>>, // each button toggles these indices
+    pub joltage: Vec<u64>,        // counter requirements, empty when the line has none
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ConfigError {
+    /// No combination of buttons produces the light diagram.
+    Unreachable,
+    /// The button wiring refers to a light the diagram does not have.
+    LightOutOfRange { button: usize, light: usize },
+    /// Too many solutions to enumerate: 2^free of them, more than `MAX_FREE_BUTTONS` free.
+    TooManyFreeButtons { free: usize },
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::Unreachable => write!(f, "no button presses produce the light diagram"),
+            ConfigError::LightOutOfRange { button, light } => {
+                write!(
+                    f,
+                    "button {button} toggles light {light}, which does not exist"
+                )
+            }
+            ConfigError::TooManyFreeButtons { free } => {
+                write!(f, "{free} buttons are free, too many combinations to try")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+/// Fixed size set of bits, for any number of lights or buttons.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct BitSet {
+    words: Vec<u64>,
+    len: usize,
 }
 
-pub fn fewest_buttons_all_machines(lines: &[Line]) -> usize {
+impl BitSet {
+    pub fn new(len: usize) -> Self {
+        BitSet {
+            words: vec![0; len.div_ceil(64)],
+            len,
+        }
+    }
+
+    pub fn from_indices(len: usize, indices: impl IntoIterator<Item = usize>) -> Self {
+        let mut bits = BitSet::new(len);
+        for idx in indices {
+            bits.toggle(idx);
+        }
+        bits
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn get(&self, idx: usize) -> bool {
+        assert!(
+            idx < self.len,
+            "bit {idx} out of range for {} bits",
+            self.len
+        );
+        self.words[idx / 64] >> (idx % 64) & 1 == 1
+    }
+
+    pub fn set(&mut self, idx: usize, value: bool) {
+        if self.get(idx) != value {
+            self.toggle(idx);
+        }
+    }
+
+    pub fn toggle(&mut self, idx: usize) {
+        assert!(
+            idx < self.len,
+            "bit {idx} out of range for {} bits",
+            self.len
+        );
+        self.words[idx / 64] ^= 1 << (idx % 64);
+    }
+
+    pub fn xor_with(&mut self, other: &BitSet) {
+        for (word, other) in self.words.iter_mut().zip(&other.words) {
+            *word ^= other;
+        }
+    }
+
+    pub fn count_ones(&self) -> usize {
+        self.words
+            .iter()
+            .map(|word| word.count_ones() as usize)
+            .sum()
+    }
+
+    /// Indices of the set bits, ascending.
+    pub fn ones(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..self.len).filter(|&idx| self.get(idx))
+    }
+}
+
+pub fn fewest_buttons_all_machines(lines: &[Line]) -> Result<usize, ConfigError> {
+    lines.iter().map(fewest_buttons_single_machine).sum()
+}
+
+pub fn fewest_presses_all_machines(lines: &[Line]) -> Result<u64, ConfigError> {
     lines
         .iter()
-        .map(|line| fewest_buttons_single_machine(line))
+        .map(|line| min_joltage_presses(line).map(|presses| presses.iter().sum::<u64>()))
         .sum()
 }
 
-pub fn fewest_buttons_single_machine(line: &Line) -> usize {
-    let min_buttons = min_buttons(line);
-    println!("{min_buttons:?}");
-    min_buttons.len()
+pub fn fewest_buttons_single_machine(line: &Line) -> Result<usize, ConfigError> {
+    analyze(line)?.fewest_buttons()
 }
 
-// ---------- parsing (simple, assumes valid) ----------
-pub fn parse_manual(input: &str) -> Vec<Line> {
-    input
-        .lines()
-        .map(str::trim)
-        .filter(|l| !l.is_empty())
-        .map(parse_line)
-        .collect()
+// ---------- parsing ----------
+
+/// One machine per line; blank lines are ignored.
+pub fn parse_manual(input: &str) -> Result<Vec<Line>, SectionError> {
+    let mut lines = Vec::new();
+    for mut section in split_sections(input) {
+        section.name = "machines".to_string();
+        lines.extend(section.parse_lines(parse_line)?);
+    }
+    Ok(lines)
 }
 
-fn parse_line(input: &str) -> Line {
-    let s = input.trim();
-    let (lights_str, after_lights) = extract_first(s, '[', ']');
+/// `[lights] (button) ... {joltage}`, checking every index against the light diagram. Errors
+/// point at the 1-based column of the offending group.
+fn parse_line(line: &str) -> Result<Line, String> {
+    let mut lights: Option<Vec<bool>> = None;
+    let mut buttons = Vec::new();
+    let mut joltage: Option<Vec<u64>> = None;
+
+    let mut rest = line;
+    loop {
+        rest = rest.trim_start();
+        let Some(open) = rest.chars().next() else {
+            break;
+        };
+        let column = line.len() - rest.len() + 1;
 
-    let lights = lights_str.chars().map(|c| c == '#').collect::<Vec<_>>();
+        let close = match open {
+            '[' => ']',
+            '(' => ')',
+            '{' => '}',
+            other => return Err(format!("column {column}: unexpected {other:?}")),
+        };
+        let end = rest[1..]
+            .find(['[', ']', '(', ')', '{', '}'])
+            .map(|end| end + 1)
+            .filter(|&end| rest[end..].starts_with(close))
+            .ok_or_else(|| format!("column {column}: {open:?} is never closed"))?;
+        let inside = &rest[1..end];
+        rest = &rest[end + 1..];
 
-    let mut rest = after_lights;
-    if let Some(i) = rest.find('{') {
-        rest = &rest[..i]; // ignore joltage
+        match (open, &lights) {
+            ('[', None) => {
+                if inside.is_empty() {
+                    return Err(format!("column {column}: empty light diagram"));
+                }
+                let diagram = inside
+                    .chars()
+                    .enumerate()
+                    .map(|(idx, light)| match light {
+                        '.' => Ok(false),
+                        '#' => Ok(true),
+                        other => Err(format!(
+                            "column {}: light {other:?} is neither '.' nor '#'",
+                            column + 1 + idx
+                        )),
+                    })
+                    .collect::<Result<_, _>>()?;
+                lights = Some(diagram);
+            }
+            ('[', Some(_)) => return Err(format!("column {column}: second light diagram")),
+            (_, None) => {
+                return Err(format!(
+                    "column {column}: {open:?} before the light diagram"
+                ));
+            }
+            (_, Some(_)) if joltage.is_some() => {
+                return Err(format!(
+                    "column {column}: {open:?} after the joltage requirements"
+                ));
+            }
+            ('(', Some(diagram)) => {
+                let button = buttons.len();
+                let mut wiring: Vec<usize> = Vec::new();
+                for item in inside.split(',') {
+                    let light: usize = item.trim().parse().map_err(|err| {
+                        format!("column {column}: bad light index {item:?}: {err}")
+                    })?;
+                    if light >= diagram.len() {
+                        return Err(format!(
+                            "column {column}: button {button} toggles light {light}, but there are only {} lights",
+                            diagram.len()
+                        ));
+                    }
+                    if wiring.contains(&light) {
+                        return Err(format!(
+                            "column {column}: button {button} lists light {light} twice"
+                        ));
+                    }
+                    wiring.push(light);
+                }
+                buttons.push(wiring);
+            }
+            // '{', the joltage requirements
+            (_, Some(diagram)) => {
+                if buttons.is_empty() {
+                    return Err(format!(
+                        "column {column}: joltage requirements before any button"
+                    ));
+                }
+                let requirements = inside
+                    .split(',')
+                    .map(|item| {
+                        item.trim()
+                            .parse::<u64>()
+                            .ok()
+                            .filter(|&value| i64::try_from(value).is_ok())
+                            .ok_or_else(|| format!("column {column}: bad joltage {item:?}"))
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                if requirements.len() != diagram.len() {
+                    return Err(format!(
+                        "column {column}: {} joltage requirements for {} lights",
+                        requirements.len(),
+                        diagram.len()
+                    ));
+                }
+                joltage = Some(requirements);
+            }
+        }
     }
 
-    let mut buttons = Vec::new();
-    while let Some((btn, after)) = try_extract_first(rest, '(', ')') {
-        let indices = btn
-            .split(|c: char| c.is_whitespace() || c == ',')
-            .filter(|t| !t.is_empty())
-            .map(|t| t.parse::<usize>().unwrap())
-            .collect::<Vec<_>>();
-        buttons.push(indices);
-        rest = after;
+    let lights = lights.ok_or("column 1: missing light diagram")?;
+    if buttons.is_empty() {
+        return Err(format!("column {}: no buttons", line.len() + 1));
     }
 
-    Line { lights, buttons }
+    Ok(Line {
+        lights,
+        buttons,
+        joltage: joltage.unwrap_or_default(),
+    })
 }
 
-fn extract_first<'a>(s: &'a str, open: char, close: char) -> (&'a str, &'a str) {
-    try_extract_first(s, open, close).unwrap()
-}
+// ---------- solving (GF(2) elimination) ----------
 
-fn try_extract_first<'a>(s: &'a str, open: char, close: char) -> Option<(&'a str, &'a str)> {
-    let start = s.find(open)?;
-    let after_open = &s[start + open.len_utf8()..];
-    let end_rel = after_open.find(close)?;
-    let inside = after_open[..end_rel].trim();
-    let rest = &after_open[end_rel + close.len_utf8()..];
-    Some((inside, rest))
+/// What elimination learned about a machine's lights, see `analyze`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Analysis {
+    buttons: usize,
+    rank: usize,
+    particular: Option<BitSet>, // one way to light the diagram, `None` when there is none
+    basis: Vec<BitSet>,         // null space: press sets that change no light, one per free button
 }
 
-// ---------- solving (≤ 16 lights) ----------
+/// Row reduces the machine's light equations once, for all the questions `Analysis` answers.
+pub fn analyze(line: &Line) -> Result<Analysis, ConfigError> {
+    let lights = line.lights.len();
+    let buttons = line.buttons.len();
 
-pub fn min_buttons(line: &Line) -> Vec<usize> {
-    assert!(line.lights.len() <= 16);
+    // one row per light: which buttons toggle it, and whether it must end up on (bit `buttons`)
+    let mut rows: Vec<BitSet> = (0..lights).map(|_| BitSet::new(buttons + 1)).collect();
+    for (button, wiring) in line.buttons.iter().enumerate() {
+        for &light in wiring {
+            let row = rows
+                .get_mut(light)
+                .ok_or(ConfigError::LightOutOfRange { button, light })?;
+            row.toggle(button); // duplicates cancel out, like pressing twice
+        }
+    }
+    for (row, &on) in rows.iter_mut().zip(&line.lights) {
+        row.set(buttons, on);
+    }
 
-    // target mask from diagram
-    let mut target: u16 = 0;
-    for (i, &on) in line.lights.iter().enumerate() {
-        if on {
-            target |= 1u16 << i;
+    // reduced row echelon form; `pivots[r]` is the button row `r` solves for
+    let mut pivots = Vec::new();
+    for button in 0..buttons {
+        let rank = pivots.len();
+        let Some(found) = (rank..lights).find(|&row| rows[row].get(button)) else {
+            continue;
+        };
+        rows.swap(rank, found);
+
+        let pivot = rows[rank].clone();
+        for (idx, row) in rows.iter_mut().enumerate() {
+            if idx != rank && row.get(button) {
+                row.xor_with(&pivot);
+            }
         }
+        pivots.push(button);
     }
 
-    // button masks
-    let btn_masks: Vec<u16> = line
-        .buttons
-        .iter()
-        .map(|idxs| {
-            let mut m = 0u16;
-            for &i in idxs {
-                m ^= 1u16 << i; // duplicates cancel automatically
+    // reachable unless a left over row reads 0 = 1; then free buttons are not pressed, and every
+    // pivot button is pressed when its row asks for it
+    let reachable = !rows[pivots.len()..].iter().any(|row| row.get(buttons));
+    let particular = reachable.then(|| {
+        let mut pressed = BitSet::new(buttons);
+        for (row, &button) in pivots.iter().enumerate() {
+            pressed.set(button, rows[row].get(buttons));
+        }
+        pressed
+    });
+
+    // pressing a free button forces every pivot button whose row contains it to flip too
+    let basis = (0..buttons)
+        .filter(|b| !pivots.contains(b))
+        .map(|button| {
+            let mut vector = BitSet::new(buttons);
+            vector.toggle(button);
+            for (row, &pivot) in pivots.iter().enumerate() {
+                if rows[row].get(button) {
+                    vector.toggle(pivot);
+                }
             }
-            m
+            vector
         })
         .collect();
 
-    // brute force all subsets; keep the one with smallest popcount
-    let m = btn_masks.len();
-    assert!(
-        m <= 63,
-        "this brute force uses u64 subset bits; raise if needed"
-    );
+    Ok(Analysis {
+        buttons,
+        rank: pivots.len(),
+        particular,
+        basis,
+    })
+}
 
-    let mut best_subset: u64 = 0;
-    let mut best_count: u32 = u32::MAX;
+impl Analysis {
+    pub fn is_reachable(&self) -> bool {
+        self.particular.is_some()
+    }
 
-    for subset in 0u64..(1u64 << m) {
-        // quick skip: if already worse than best, don't bother computing
-        let count = subset.count_ones();
-        if count >= best_count {
-            continue;
-        }
+    /// Rank of the button matrix over GF(2): how many buttons act independently.
+    pub fn rank(&self) -> usize {
+        self.rank
+    }
 
-        // compute XOR of chosen buttons
-        let mut x: u16 = 0;
-        for i in 0..m {
-            if ((subset >> i) & 1) == 1 {
-                x ^= btn_masks[i];
+    /// Buttons toggling the same lights as some combination of the other buttons, ascending.
+    ///
+    /// Those are exactly the buttons some light preserving press set contains.
+    pub fn redundant_buttons(&self) -> Vec<usize> {
+        let mut redundant = BitSet::new(self.buttons);
+        for vector in &self.basis {
+            for button in vector.ones() {
+                redundant.set(button, true);
             }
         }
+        redundant.ones().collect()
+    }
 
-        if x == target {
-            best_subset = subset;
-            best_count = count;
-            if best_count == 0 {
-                break;
-            }
+    /// Every press set producing the diagram, in Gray code order of the null space combinations.
+    fn solutions(&self) -> Result<impl Iterator<Item = BitSet> + '_, ConfigError> {
+        let particular = self.particular.as_ref().ok_or(ConfigError::Unreachable)?;
+        if self.basis.len() > MAX_FREE_BUTTONS {
+            return Err(ConfigError::TooManyFreeButtons {
+                free: self.basis.len(),
+            });
         }
+
+        let steps = (1u64..1 << self.basis.len()).scan(particular.clone(), |current, step| {
+            current.xor_with(&self.basis[step.trailing_zeros() as usize]);
+            Some(current.clone())
+        });
+        Ok(std::iter::once(particular.clone()).chain(steps))
     }
 
-    // return indices of chosen buttons
-    let mut chosen = Vec::new();
-    for i in 0..m {
-        if ((best_subset >> i) & 1) == 1 {
-            chosen.push(i);
+    /// Fewest buttons pressed to light the diagram.
+    pub fn fewest_buttons(&self) -> Result<usize, ConfigError> {
+        Ok(self.optimal()?.0)
+    }
+
+    /// How many distinct press sets light the diagram with the fewest buttons.
+    pub fn optimal_count(&self) -> Result<u64, ConfigError> {
+        Ok(self.optimal()?.1)
+    }
+
+    /// The press sets (as ascending button indices) lighting the diagram with the fewest buttons,
+    /// computed as they are iterated.
+    pub fn optimal_press_sets(&self) -> Result<impl Iterator<Item = Vec<usize>> + '_, ConfigError> {
+        let fewest = self.fewest_buttons()?;
+        Ok(self
+            .solutions()?
+            .filter(move |pressed| pressed.count_ones() == fewest)
+            .map(|pressed| pressed.ones().collect()))
+    }
+
+    fn optimal(&self) -> Result<(usize, u64), ConfigError> {
+        let optimal = self.solutions()?.map(|pressed| pressed.count_ones()).fold(
+            (usize::MAX, 0),
+            |(fewest, count), pressed| match pressed.cmp(&fewest) {
+                std::cmp::Ordering::Less => (pressed, 1),
+                std::cmp::Ordering::Equal => (fewest, count + 1),
+                std::cmp::Ordering::Greater => (fewest, count),
+            },
+        );
+        Ok(optimal)
+    }
+}
+
+/// Indices of the fewest buttons that turn on exactly the lights of the diagram.
+pub fn min_buttons(line: &Line) -> Result<Vec<usize>, ConfigError> {
+    let analysis = analyze(line)?;
+    let first = analysis.optimal_press_sets()?.next();
+    Ok(first.expect("a reachable diagram has an optimal press set"))
+}
+
+// ---------- solving (integer program) ----------
+
+/// How often to press each button so every counter reaches its joltage, pressing fewest overall.
+pub fn min_joltage_presses(line: &Line) -> Result<Vec<u64>, ConfigError> {
+    let buttons = line.buttons.len();
+
+    // one row per counter: how much each button adds to it
+    let mut matrix = vec![vec![0; buttons]; line.joltage.len()];
+    for (button, wiring) in line.buttons.iter().enumerate() {
+        for &light in wiring {
+            let row = matrix
+                .get_mut(light)
+                .ok_or(ConfigError::LightOutOfRange { button, light })?;
+            row[button] += 1;
         }
     }
-    chosen
+    let rhs = line.joltage.iter().map(|&joltage| joltage as i64).collect();
+
+    match Problem::new(buttons, matrix, rhs).solve() {
+        Ok(solution) => Ok(solution.values.iter().map(|&p| p as u64).collect()),
+        // every row is non-negative, so every button is bounded and infeasible is all that's left
+        Err(_) => Err(ConfigError::Unreachable),
+    }
 }
@@ -101,7 +101,7 @@ pub fn traverse_paths(input: &HashMap<String, Vec<String>>) -> i32 {
     let mut paths = 0;
 
     let mut next_iteration = start.clone();
-    while next_iteration.len() > 0 {
+    while !next_iteration.is_empty() {
         let mut current_iteration = Vec::new();
 
         for branch in next_iteration {
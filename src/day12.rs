@@ -18,6 +18,8 @@
 
 
 */
+use crate::sections::{SectionError, split_sections};
+
 pub const INPUT: &str = include_str!("inputs/day12.txt");
 
 pub const TEST_INPUT: &str = r#"0:
@@ -59,20 +61,20 @@ pub const TEST_INPUT: &str = r#"0:
 // Core types
 // -------------------------
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 struct Shape {
     id: usize,
     cells: Vec<(i32, i32)>, // '#' only, normalized to top-left
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 struct RegionSpec {
     w: usize,
     h: usize,
     counts: Vec<usize>, // counts[shape_id]
 }
 
-#[derive(Debug)]
+#[derive(Debug, PartialEq, Eq)]
 pub struct Puzzle {
     shapes: Vec<Shape>,
     regions: Vec<RegionSpec>,
@@ -82,92 +84,124 @@ pub struct Puzzle {
 // Parsing (single input containing shapes + regions)
 // -------------------------
 
-pub fn parse_puzzle(input: &str) -> Puzzle {
+pub fn parse_puzzle(input: &str) -> Result<Puzzle, SectionError> {
     let mut shapes: Vec<Shape> = Vec::new();
-    let mut regions: Vec<RegionSpec> = Vec::new();
-
-    let mut lines = input.lines().peekable();
-
-    while let Some(line) = lines.next() {
-        let line = line.trim_end();
-        if line.trim().is_empty() {
-            continue;
-        }
-
-        let Some((lhs, rhs)) = line.split_once(':') else {
-            continue;
-        };
-        let lhs = lhs.trim();
-        let rhs = rhs.trim();
-
-        // Region: "{w}x{h}: counts..."
-        if let Some((w_str, h_str)) = lhs.split_once('x') {
-            if let (Ok(w), Ok(h)) = (w_str.trim().parse::<usize>(), h_str.trim().parse::<usize>()) {
-                let counts: Vec<usize> = rhs
-                    .split_whitespace()
-                    .map(|x| x.parse::<usize>().expect("count"))
-                    .collect();
-
-                regions.push(RegionSpec { w, h, counts });
-                continue;
+    let mut regions: Vec<(usize, RegionSpec)> = Vec::new(); // (line, region)
+
+    // blank lines are optional: a header ("{id}:" or "{w}x{h}:") also ends the grid before it
+    for section in split_sections(input) {
+        let mut shape: Option<(usize, usize, Vec<Vec<char>>)> = None; // (line, id, grid)
+
+        for (line_no, line) in section.lines() {
+            let header = looks_like_header(line);
+            if header.is_some()
+                && let Some(done) = shape.take()
+            {
+                shapes.push(finish_shape(done)?);
             }
-        }
-
-        // Shape header: "{id}:" with empty rhs
-        if rhs.is_empty() {
-            let id: usize = lhs.parse().expect("shape id");
-
-            let mut grid: Vec<Vec<char>> = Vec::new();
 
-            // Read until blank line or next header (shape or region).
-            while let Some(&peek) = lines.peek() {
-                let p = peek.trim_end();
-                if p.trim().is_empty() {
-                    lines.next(); // consume blank line
-                    break;
+            match (header, &mut shape) {
+                // Shape: "{id}:" header followed by its grid
+                (Some(Header::Shape(id)), _) => shape = Some((line_no, id, Vec::new())),
+                (None, Some((_, _, grid))) => grid.push(line.trim_end().chars().collect()),
+                // Regions: "{w}x{h}: counts..."
+                _ => {
+                    let region = parse_region(line.trim()).map_err(|err| SectionError {
+                        section: "regions".to_string(),
+                        line: line_no,
+                        message: format!("{line:?}: {err}"),
+                    })?;
+                    regions.push((line_no, region));
                 }
-                if looks_like_header(p) {
-                    break;
-                }
-                grid.push(p.chars().collect());
-                lines.next();
             }
+        }
 
-            let cells = normalize_cells(extract_hash_cells(&grid));
-            shapes.push(Shape { id, cells });
+        if let Some(done) = shape {
+            shapes.push(finish_shape(done)?);
         }
     }
 
     shapes.sort_by_key(|s| s.id);
 
-    for r in &regions {
-        assert!(
-            r.counts.len() == shapes.len(),
-            "Region {}x{} has {} counts, but there are {} shapes",
-            r.w,
-            r.h,
-            r.counts.len(),
-            shapes.len()
-        );
+    let mut specs = Vec::with_capacity(regions.len());
+    for (line_no, r) in regions {
+        if r.counts.len() != shapes.len() {
+            return Err(SectionError {
+                section: "regions".to_string(),
+                line: line_no,
+                message: format!(
+                    "region {}x{} has {} counts, but there are {} shapes",
+                    r.w,
+                    r.h,
+                    r.counts.len(),
+                    shapes.len()
+                ),
+            });
+        }
+        specs.push(r);
     }
 
-    Puzzle { shapes, regions }
+    Ok(Puzzle {
+        shapes,
+        regions: specs,
+    })
 }
 
-fn looks_like_header(line: &str) -> bool {
-    let l = line.trim();
-    let Some((lhs, _)) = l.split_once(':') else {
-        return false;
-    };
+enum Header {
+    Shape(usize),
+    Region,
+}
+
+fn looks_like_header(line: &str) -> Option<Header> {
+    let (lhs, _) = line.trim().split_once(':')?;
     let lhs = lhs.trim();
 
-    if lhs.parse::<usize>().is_ok() {
-        return true; // "N:"
+    if let Ok(id) = lhs.parse::<usize>() {
+        return Some(Header::Shape(id)); // "N:"
     }
-    if let Some((w_str, h_str)) = lhs.split_once('x') {
-        return w_str.trim().parse::<usize>().is_ok() && h_str.trim().parse::<usize>().is_ok();
+    let (w_str, h_str) = lhs.split_once('x')?;
+    (w_str.trim().parse::<usize>().is_ok() && h_str.trim().parse::<usize>().is_ok())
+        .then_some(Header::Region) // "WxH:"
+}
+
+fn finish_shape(
+    (line_no, id, grid): (usize, usize, Vec<Vec<char>>),
+) -> Result<Shape, SectionError> {
+    if grid.is_empty() {
+        return Err(SectionError {
+            section: format!("shape {id}"),
+            line: line_no,
+            message: "shape has no grid".to_string(),
+        });
     }
-    false
+
+    let cells = normalize_cells(extract_hash_cells(&grid));
+    Ok(Shape { id, cells })
+}
+
+fn parse_region(line: &str) -> Result<RegionSpec, String> {
+    let malformed = || "expected `{w}x{h}: counts...`".to_string();
+
+    let (lhs, rhs) = line.split_once(':').ok_or_else(malformed)?;
+    let (w_str, h_str) = lhs.trim().split_once('x').ok_or_else(malformed)?;
+
+    let w = w_str
+        .trim()
+        .parse::<usize>()
+        .map_err(|err| err.to_string())?;
+    let h = h_str
+        .trim()
+        .parse::<usize>()
+        .map_err(|err| err.to_string())?;
+    let counts = rhs
+        .split_whitespace()
+        .map(|x| {
+            x.parse::<usize>()
+                .map_err(|err| format!("count {x:?}: {err}"))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(RegionSpec { w, h, counts })
 }
 
 fn extract_hash_cells(grid: &[Vec<char>]) -> Vec<(i32, i32)> {
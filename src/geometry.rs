@@ -0,0 +1,389 @@
+/*
+    geometry:
+
+    Lattice points, rectangles and rectilinear polygons (every edge horizontal or vertical), as in
+    day09 where the red tiles are the vertices of a loop of green tiles.
+
+    Regions are sets of lattice points: a polygon contains the points on its boundary and the ones
+    enclosed by it, a rectangle every point between its corners. So a rectangle between two edges
+    one unit apart is contained, even though the real area between them is not.
+
+    Containment queries go through a `CompressedGrid`: every x holding a vertex gets its own
+    column, every non-empty run of xs between two of them shares one, plus a column on each side
+    for the ring around the polygon; the same for rows. Each cell is then either fully inside or
+    fully outside, and a flood fill from the ring finds the outside ones.
+*/
+use crate::compressed_grid::{Axis, CompressedGrid};
+use std::collections::VecDeque;
+use std::fmt;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Point {
+    pub x: i64,
+    pub y: i64,
+}
+
+impl Point {
+    pub fn new(x: i64, y: i64) -> Self {
+        Point { x, y }
+    }
+}
+
+/// Axis-aligned rectangle of lattice points, corners included.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Rectangle {
+    pub min: Point,
+    pub max: Point,
+}
+
+impl Rectangle {
+    /// The rectangle with `a` and `b` as opposite corners, in any order.
+    pub fn from_corners(a: Point, b: Point) -> Self {
+        Rectangle {
+            min: Point::new(a.x.min(b.x), a.y.min(b.y)),
+            max: Point::new(a.x.max(b.x), a.y.max(b.y)),
+        }
+    }
+
+    pub fn width(&self) -> u64 {
+        self.max.x.abs_diff(self.min.x) + 1
+    }
+
+    pub fn height(&self) -> u64 {
+        self.max.y.abs_diff(self.min.y) + 1
+    }
+
+    /// Number of lattice points (tiles) covered.
+    pub fn tiles(&self) -> u64 {
+        self.width() * self.height()
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Orientation {
+    /// With y growing upwards; on a screen, where y grows downwards, it looks clockwise.
+    CounterClockwise,
+    Clockwise,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PolygonError {
+    TooFewVertices(usize),
+    /// Vertex `index` is the same point as the one before it.
+    RepeatedVertex {
+        index: usize,
+    },
+    /// The edge from vertex `index` to the next one is neither horizontal nor vertical.
+    NotAxisAligned {
+        index: usize,
+    },
+    /// The edges starting at vertices `first` and `second` cross, touch or overlap.
+    SelfIntersecting {
+        first: usize,
+        second: usize,
+    },
+}
+
+impl fmt::Display for PolygonError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PolygonError::TooFewVertices(count) => {
+                write!(f, "a polygon needs at least 4 vertices, found {count}")
+            }
+            PolygonError::RepeatedVertex { index } => {
+                write!(f, "vertex {index} repeats the previous one")
+            }
+            PolygonError::NotAxisAligned { index } => {
+                write!(f, "edge from vertex {index} is not horizontal or vertical")
+            }
+            PolygonError::SelfIntersecting { first, second } => {
+                write!(f, "edges from vertices {first} and {second} intersect")
+            }
+        }
+    }
+}
+
+impl std::error::Error for PolygonError {}
+
+/// A simple closed polygon with axis-aligned edges; the last vertex connects back to the first.
+#[derive(Clone, Debug)]
+pub struct Polygon {
+    vertices: Vec<Point>,
+    raster: Raster,
+}
+
+impl Polygon {
+    pub fn new(vertices: Vec<Point>) -> Result<Self, PolygonError> {
+        if vertices.len() < 4 {
+            return Err(PolygonError::TooFewVertices(vertices.len()));
+        }
+
+        let n = vertices.len();
+        let edge = |idx: usize| (vertices[idx], vertices[(idx + 1) % n]);
+
+        for idx in 0..n {
+            let (from, to) = edge(idx);
+            if from == to {
+                return Err(PolygonError::RepeatedVertex {
+                    index: (idx + 1) % n,
+                });
+            }
+            if from.x != to.x && from.y != to.y {
+                return Err(PolygonError::NotAxisAligned { index: idx });
+            }
+        }
+
+        for first in 0..n {
+            for second in first + 1..n {
+                let adjacent = second == first + 1 || (first == 0 && second == n - 1);
+                if edges_meet(edge(first), edge(second), adjacent) {
+                    return Err(PolygonError::SelfIntersecting { first, second });
+                }
+            }
+        }
+
+        let raster = Raster::new(&vertices);
+        Ok(Polygon { vertices, raster })
+    }
+
+    pub fn vertices(&self) -> &[Point] {
+        &self.vertices
+    }
+
+    fn edges(&self) -> impl Iterator<Item = (Point, Point)> + '_ {
+        self.vertices
+            .iter()
+            .zip(self.vertices.iter().cycle().skip(1))
+            .map(|(&from, &to)| (from, to))
+    }
+
+    /// Twice the signed area (shoelace formula), positive when counter-clockwise.
+    fn signed_double_area(&self) -> i128 {
+        self.edges()
+            .map(|(a, b)| a.x as i128 * b.y as i128 - b.x as i128 * a.y as i128)
+            .sum()
+    }
+
+    pub fn orientation(&self) -> Orientation {
+        if self.signed_double_area() > 0 {
+            Orientation::CounterClockwise
+        } else {
+            Orientation::Clockwise
+        }
+    }
+
+    /// Real area enclosed by the edges.
+    pub fn area(&self) -> u128 {
+        // axis-aligned edges between lattice points always enclose a whole area
+        self.signed_double_area().unsigned_abs() / 2
+    }
+
+    /// Lattice points on the edges, which is also the perimeter.
+    pub fn boundary_points(&self) -> u128 {
+        self.edges()
+            .map(|(a, b)| (a.x.abs_diff(b.x) + a.y.abs_diff(b.y)) as u128)
+            .sum()
+    }
+
+    /// Lattice points strictly inside, by Pick's theorem: A = I + B / 2 - 1.
+    pub fn interior_points(&self) -> u128 {
+        self.area() + 1 - self.boundary_points() / 2
+    }
+
+    /// Lattice points inside or on the boundary: the tiles the polygon covers.
+    pub fn lattice_points(&self) -> u128 {
+        self.interior_points() + self.boundary_points()
+    }
+
+    /// Whether `point` is on the boundary or inside.
+    pub fn contains(&self, point: Point) -> bool {
+        self.contains_rectangle(&Rectangle::from_corners(point, point))
+    }
+
+    /// Whether every lattice point of `rectangle` is on the boundary or inside.
+    pub fn contains_rectangle(&self, rectangle: &Rectangle) -> bool {
+        self.raster.all_inside(rectangle)
+    }
+}
+
+/// Whether two axis-aligned edges share any point; adjacent edges may share their common vertex.
+fn edges_meet(a: (Point, Point), b: (Point, Point), adjacent: bool) -> bool {
+    let bounds = |(p, q): (Point, Point)| (p.x.min(q.x), p.x.max(q.x), p.y.min(q.y), p.y.max(q.y));
+    let (ax0, ax1, ay0, ay1) = bounds(a);
+    let (bx0, bx1, by0, by1) = bounds(b);
+
+    let overlap_x = ax0.max(bx0)..=ax1.min(bx1);
+    let overlap_y = ay0.max(by0)..=ay1.min(by1);
+    if overlap_x.is_empty() || overlap_y.is_empty() {
+        return false;
+    }
+
+    // the overlap is a single point exactly when it is the vertex the two edges share
+    !adjacent || overlap_x.start() != overlap_x.end() || overlap_y.start() != overlap_y.end()
+}
+
+/// Compressed grid of the polygon, see the module comment.
+#[derive(Clone, Debug)]
+struct Raster {
+    grid: CompressedGrid,
+}
+
+impl Raster {
+    fn new(vertices: &[Point]) -> Self {
+        let cols = Self::cuts(vertices.iter().map(|p| p.x));
+        let rows = Self::cuts(vertices.iter().map(|p| p.y));
+        let (width, height) = (cols.runs(), rows.runs());
+
+        let mut border = vec![false; width * height];
+        for (idx, &from) in vertices.iter().enumerate() {
+            let to = vertices[(idx + 1) % vertices.len()];
+            let (left, right) = (cols.index(from.x.min(to.x)), cols.index(from.x.max(to.x)));
+            let (top, bottom) = (rows.index(from.y.min(to.y)), rows.index(from.y.max(to.y)));
+            for row in top.unwrap()..=bottom.unwrap() {
+                for col in left.unwrap()..=right.unwrap() {
+                    border[row * width + col] = true;
+                }
+            }
+        }
+
+        // everything reachable from the ring around the polygon without crossing it is outside
+        let mut inside = vec![true; width * height];
+        let mut queue = VecDeque::from([(0usize, 0usize)]);
+        inside[0] = false;
+        while let Some((row, col)) = queue.pop_front() {
+            let neighbours = [
+                (row.wrapping_sub(1), col),
+                (row + 1, col),
+                (row, col.wrapping_sub(1)),
+                (row, col + 1),
+            ];
+            for (row, col) in neighbours {
+                if row < height && col < width {
+                    let idx = row * width + col;
+                    if !border[idx] && inside[idx] {
+                        inside[idx] = false;
+                        queue.push_back((row, col));
+                    }
+                }
+            }
+        }
+
+        Raster {
+            grid: CompressedGrid::from_cells(cols, rows, &inside),
+        }
+    }
+
+    /// Cuts making every value its own run, plus a run of one on each side for the ring.
+    fn cuts(values: impl Iterator<Item = i64>) -> Axis {
+        let mut cuts: Vec<i64> = values.flat_map(|value| [value, value + 1]).collect();
+        let (min, max) = (*cuts.iter().min().unwrap(), *cuts.iter().max().unwrap());
+        cuts.extend([min - 1, max + 1]);
+        Axis::new(cuts)
+    }
+
+    fn all_inside(&self, rectangle: &Rectangle) -> bool {
+        self.grid.is_filled(rectangle)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn polygon(vertices: &[(i64, i64)]) -> Result<Polygon, PolygonError> {
+        Polygon::new(vertices.iter().map(|&(x, y)| Point::new(x, y)).collect())
+    }
+
+    // a U whose notch is one unit wide: no lattice point lies in the notch
+    const NARROW_U: [(i64, i64); 8] = [
+        (0, 0),
+        (7, 0),
+        (7, 5),
+        (4, 5),
+        (4, 2),
+        (3, 2),
+        (3, 5),
+        (0, 5),
+    ];
+
+    #[test]
+    fn test_measures() {
+        let square = polygon(&[(0, 0), (0, 2), (2, 2), (2, 0)]).unwrap();
+        assert_eq!(square.orientation(), Orientation::Clockwise);
+        assert_eq!(square.area(), 4);
+        assert_eq!(square.boundary_points(), 8);
+        assert_eq!(square.interior_points(), 1);
+        assert_eq!(square.lattice_points(), 9);
+
+        let u = polygon(&NARROW_U).unwrap();
+        assert_eq!(u.orientation(), Orientation::CounterClockwise);
+        assert_eq!(u.area(), 35 - 3);
+        assert_eq!(u.lattice_points(), 48);
+
+        // the lattice point count agrees with asking every point of the bounding box
+        let covered = (-1..=8)
+            .flat_map(|x| (-1..=6).map(move |y| Point::new(x, y)))
+            .filter(|&p| u.contains(p))
+            .count();
+        assert_eq!(covered as u128, u.lattice_points());
+    }
+
+    #[test]
+    fn test_containment() {
+        let u = polygon(&NARROW_U).unwrap();
+
+        assert!(u.contains(Point::new(3, 4)));
+        assert!(u.contains(Point::new(5, 3)));
+        assert!(!u.contains(Point::new(8, 3)));
+        assert!(!u.contains(Point::new(-1, 0)));
+
+        let rect = |a: (i64, i64), b: (i64, i64)| {
+            Rectangle::from_corners(Point::new(a.0, a.1), Point::new(b.0, b.1))
+        };
+        assert!(u.contains_rectangle(&rect((0, 0), (7, 2))));
+        assert!(u.contains_rectangle(&rect((0, 0), (7, 5)))); // the notch has no lattice points
+        assert!(!u.contains_rectangle(&rect((0, 0), (8, 5))));
+
+        let wide = polygon(&[
+            (0, 0),
+            (7, 0),
+            (7, 5),
+            (5, 5),
+            (5, 2),
+            (2, 2),
+            (2, 5),
+            (0, 5),
+        ])
+        .unwrap();
+        assert!(!wide.contains_rectangle(&rect((0, 0), (7, 5))));
+        assert!(!wide.contains(Point::new(3, 3)));
+        assert!(wide.contains_rectangle(&rect((5, 0), (7, 5))));
+        assert_eq!(rect((5, 0), (7, 5)).tiles(), 18);
+    }
+
+    #[test]
+    fn test_validation() {
+        assert_eq!(
+            polygon(&[(0, 0), (1, 0), (1, 1)]).unwrap_err(),
+            PolygonError::TooFewVertices(3)
+        );
+        assert_eq!(
+            polygon(&[(0, 0), (2, 0), (2, 2), (1, 3), (0, 2)]).unwrap_err(),
+            PolygonError::NotAxisAligned { index: 2 }
+        );
+        assert_eq!(
+            polygon(&[(0, 0), (2, 0), (2, 0), (2, 2), (0, 2)]).unwrap_err(),
+            PolygonError::RepeatedVertex { index: 2 }
+        );
+        // a figure eight crossing itself
+        assert_eq!(
+            polygon(&[(0, 0), (2, 0), (2, 4), (4, 4), (4, 2), (0, 2)]).unwrap_err(),
+            PolygonError::SelfIntersecting {
+                first: 1,
+                second: 4
+            }
+        );
+        // doubling back over the previous edge
+        assert!(polygon(&[(0, 0), (3, 0), (1, 0), (1, 2), (0, 2)]).is_err());
+    }
+}
@@ -0,0 +1,511 @@
+/*
+    ilp:
+
+    Small exact integer linear programs: minimize c . x subject to A x = b, x >= 0 integer, with
+    A, b and c given as integers. Every step stays in exact integer arithmetic (i128), there are
+    no floating point roundings to second guess.
+
+    Fraction-free Gauss-Jordan elimination brings A x = b into reduced row echelon form, scaling
+    rows instead of dividing and keeping them small by their gcd. Each pivot variable is then an
+    exact function of the free variables, so enumerating the free ones within their upper bounds
+    finds every integer solution: a pivot has to come out non-negative, divisible by its
+    coefficient and within its own bound. Branches whose cost can no longer get below the best
+    one are cut.
+
+    Upper bounds come from rows without mixed signs (x_j <= b_i / a_ij when every a_i is >= 0) or
+    are given explicitly. A free variable without one can't be enumerated and `solve` gives up
+    with `IlpError::Unbounded`, even when a combination of the rows would bound it: bounds are
+    only read off single rows, there is no LP relaxation step. Pass `with_upper_bounds` in that
+    case. That suits the puzzles, whose matrices are small and non-negative with few free
+    variables.
+
+    Infeasibility comes with a certificate: either multipliers y with y A = 0 but y . b != 0, so
+    not even a rational solution exists, or the free variables and bounds that were exhausted.
+*/
+use std::fmt;
+
+/// Minimize `costs . x` subject to `matrix x = rhs`, `x >= 0` integer, `x <= upper` where given.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Problem {
+    matrix: Vec<Vec<i64>>, // one row per constraint, one column per variable
+    rhs: Vec<i64>,
+    costs: Vec<i64>,
+    upper: Vec<Option<u64>>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Solution {
+    pub values: Vec<i128>,
+    pub objective: i128,
+}
+
+/// Why a problem has no solution, checkable without trusting the solver.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Certificate {
+    /// `multipliers . matrix` is all zeros while `multipliers . rhs` isn't.
+    Inconsistent { multipliers: Vec<i128> },
+    /// Every assignment of these `(variable, upper bound)` left a pivot negative or fractional;
+    /// a bound of -1 means the rows leave no value at all.
+    Exhausted { free: Vec<(usize, i128)> },
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum IlpError {
+    Infeasible(Certificate),
+    Unbounded { variable: usize },
+}
+
+impl fmt::Display for IlpError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IlpError::Infeasible(Certificate::Inconsistent { .. }) => {
+                write!(f, "the constraints contradict each other")
+            }
+            IlpError::Infeasible(Certificate::Exhausted { free }) => {
+                write!(f, "no integer solution within the bounds of {free:?}")
+            }
+            IlpError::Unbounded { variable } => {
+                write!(f, "variable {variable} has no upper bound to search within")
+            }
+        }
+    }
+}
+
+impl std::error::Error for IlpError {}
+
+impl Problem {
+    /// Minimizes the sum of `variables` variables, with no bounds beyond what the rows imply.
+    ///
+    /// Panics when the rows and `rhs` don't line up.
+    pub fn new(variables: usize, matrix: Vec<Vec<i64>>, rhs: Vec<i64>) -> Self {
+        assert_eq!(
+            matrix.len(),
+            rhs.len(),
+            "one right hand side per row expected"
+        );
+        assert!(
+            matrix.iter().all(|row| row.len() == variables),
+            "rows of different length"
+        );
+
+        Problem {
+            matrix,
+            rhs,
+            costs: vec![1; variables],
+            upper: vec![None; variables],
+        }
+    }
+
+    pub fn with_costs(mut self, costs: Vec<i64>) -> Self {
+        assert_eq!(
+            costs.len(),
+            self.variables(),
+            "one cost per variable expected"
+        );
+        self.costs = costs;
+        self
+    }
+
+    pub fn with_upper_bounds(mut self, upper: Vec<Option<u64>>) -> Self {
+        assert_eq!(
+            upper.len(),
+            self.variables(),
+            "one bound per variable expected"
+        );
+        self.upper = upper;
+        self
+    }
+
+    pub fn variables(&self) -> usize {
+        self.costs.len()
+    }
+
+    pub fn constraints(&self) -> usize {
+        self.rhs.len()
+    }
+
+    /// `(multipliers . matrix, multipliers . rhs)`, to check an inconsistency certificate.
+    pub fn combine(&self, multipliers: &[i128]) -> (Vec<i128>, i128) {
+        let mut combined = vec![0; self.variables()];
+        let mut rhs = 0;
+        for ((row, &b), &y) in self.matrix.iter().zip(&self.rhs).zip(multipliers) {
+            for (value, &a) in combined.iter_mut().zip(row) {
+                *value += y * a as i128;
+            }
+            rhs += y * b as i128;
+        }
+        (combined, rhs)
+    }
+
+    /// Upper bounds implied by rows without mixed signs, tightened by the explicit ones.
+    fn bounds(&self) -> Vec<Option<i128>> {
+        let mut bounds: Vec<Option<i128>> = self.upper.iter().map(|u| u.map(i128::from)).collect();
+
+        for (row, &b) in self.matrix.iter().zip(&self.rhs) {
+            let sign = if row.iter().all(|&a| a >= 0) {
+                1
+            } else if row.iter().all(|&a| a <= 0) {
+                -1
+            } else {
+                continue;
+            };
+
+            for (bound, &a) in bounds.iter_mut().zip(row) {
+                if a != 0 {
+                    // a row that can't reach its right hand side leaves no value at all
+                    let (a, b) = (sign * a as i128, sign * b as i128);
+                    let implied = if b < 0 { -1 } else { b / a };
+                    *bound = Some(bound.map_or(implied, |bound| bound.min(implied)));
+                }
+            }
+        }
+
+        // a variable in no constraint is best left at 0, unless it pays to raise it forever
+        for (variable, bound) in bounds.iter_mut().enumerate() {
+            if self.matrix.iter().all(|row| row[variable] == 0) && self.costs[variable] >= 0 {
+                *bound = Some(0);
+            }
+        }
+
+        bounds
+    }
+
+    pub fn solve(&self) -> Result<Solution, IlpError> {
+        let (variables, constraints) = (self.variables(), self.constraints());
+        let bounds = self.bounds();
+
+        // each row: coefficients, right hand side, then how it combines the original rows
+        let width = variables + 1 + constraints;
+        let mut rows: Vec<Vec<i128>> = (0..constraints)
+            .map(|idx| {
+                let mut row = vec![0; width];
+                for (value, &a) in row.iter_mut().zip(&self.matrix[idx]) {
+                    *value = a as i128;
+                }
+                row[variables] = self.rhs[idx] as i128;
+                row[variables + 1 + idx] = 1;
+                row
+            })
+            .collect();
+
+        let mut pivots = Vec::new();
+        for variable in 0..variables {
+            let rank = pivots.len();
+            let Some(found) = (rank..constraints).find(|&row| rows[row][variable] != 0) else {
+                continue;
+            };
+            rows.swap(rank, found);
+            if rows[rank][variable] < 0 {
+                rows[rank].iter_mut().for_each(|value| *value = -*value);
+            }
+
+            let pivot = rows[rank].clone();
+            for (idx, row) in rows.iter_mut().enumerate() {
+                let factor = row[variable];
+                if idx == rank || factor == 0 {
+                    continue;
+                }
+                for (value, &p) in row.iter_mut().zip(&pivot) {
+                    *value = *value * pivot[variable] - factor * p;
+                }
+                let divisor = row.iter().fold(0, |acc, &value| gcd(acc, value));
+                if divisor > 1 {
+                    row.iter_mut().for_each(|value| *value /= divisor);
+                }
+            }
+            pivots.push(variable);
+        }
+
+        // a left over row reading 0 = something else
+        if let Some(row) = rows[pivots.len()..].iter().find(|row| row[variables] != 0) {
+            return Err(IlpError::Infeasible(Certificate::Inconsistent {
+                multipliers: row[variables + 1..].to_vec(),
+            }));
+        }
+
+        let free: Vec<usize> = (0..variables).filter(|v| !pivots.contains(v)).collect();
+        let mut free_bounds = Vec::with_capacity(free.len());
+        for &variable in &free {
+            let bound = bounds[variable].ok_or(IlpError::Unbounded { variable })?;
+            free_bounds.push((variable, bound));
+        }
+
+        let search = Search::new(self, &rows[..pivots.len()], &pivots, &free_bounds, &bounds);
+        let mut values = vec![0; variables];
+        let mut best = None;
+        search.run(0, 0, &mut values, &mut best);
+
+        best.map(|(objective, values)| Solution { values, objective })
+            .ok_or(IlpError::Infeasible(Certificate::Exhausted {
+                free: free_bounds,
+            }))
+    }
+}
+
+fn gcd(a: i128, b: i128) -> i128 {
+    if b == 0 { a.abs() } else { gcd(b, a % b) }
+}
+
+/// Depth first enumeration of the free variables of a reduced system.
+struct Search<'a> {
+    rows: &'a [Vec<i128>], // row `r` solves for `pivots[r]`, right hand side at `variables`
+    pivots: &'a [usize],
+    free: &'a [(usize, i128)],
+    costs: Vec<i128>,
+    bounds: &'a [Option<i128>],
+    variables: usize,
+    // least cost the free variables from `depth` on and the pivots can still add, `None` when a
+    // pivot with negative cost has no bound
+    rest: Vec<Option<i128>>,
+}
+
+impl<'a> Search<'a> {
+    fn new(
+        problem: &Problem,
+        rows: &'a [Vec<i128>],
+        pivots: &'a [usize],
+        free: &'a [(usize, i128)],
+        bounds: &'a [Option<i128>],
+    ) -> Self {
+        let costs: Vec<i128> = problem.costs.iter().map(|&c| c as i128).collect();
+        let least = |variable: usize, bound: Option<i128>| match costs[variable] {
+            0.. => Some(0),
+            cost => bound.map(|bound| cost * bound),
+        };
+
+        let pivot_least = pivots
+            .iter()
+            .map(|&p| least(p, bounds[p]))
+            .sum::<Option<i128>>();
+        let mut rest = vec![pivot_least; free.len() + 1];
+        for depth in (0..free.len()).rev() {
+            let (variable, bound) = free[depth];
+            rest[depth] = rest[depth + 1]
+                .zip(least(variable, Some(bound)))
+                .map(|(a, b)| a + b);
+        }
+
+        Search {
+            rows,
+            pivots,
+            free,
+            costs,
+            bounds,
+            variables: problem.variables(),
+            rest,
+        }
+    }
+
+    fn run(
+        &self,
+        depth: usize,
+        cost: i128,
+        values: &mut [i128],
+        best: &mut Option<(i128, Vec<i128>)>,
+    ) {
+        if let (Some(rest), Some((best, _))) = (self.rest[depth], best.as_ref())
+            && cost + rest >= *best
+        {
+            return;
+        }
+
+        if let Some(&(variable, bound)) = self.free.get(depth) {
+            for value in 0..=bound {
+                values[variable] = value;
+                self.run(depth + 1, cost + value * self.costs[variable], values, best);
+            }
+            values[variable] = 0;
+            return;
+        }
+
+        let mut cost = cost;
+        for (row, &pivot) in self.rows.iter().zip(self.pivots) {
+            let rest: i128 = self.free.iter().map(|&(f, _)| row[f] * values[f]).sum();
+            let value = row[self.variables] - rest;
+            if value < 0 || value % row[pivot] != 0 {
+                return;
+            }
+            values[pivot] = value / row[pivot];
+            if self.bounds[pivot].is_some_and(|bound| values[pivot] > bound) {
+                return;
+            }
+            cost += values[pivot] * self.costs[pivot];
+        }
+
+        if best.as_ref().is_none_or(|(best, _)| cost < *best) {
+            *best = Some((cost, values.to_vec()));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::day10::{TEST_INPUT, parse_manual};
+    use crate::lcg::Lcg;
+
+    fn check(problem: &Problem, solution: &Solution) {
+        for (row, &b) in problem.matrix.iter().zip(&problem.rhs) {
+            let lhs: i128 = row
+                .iter()
+                .zip(&solution.values)
+                .map(|(&a, &x)| a as i128 * x)
+                .sum();
+            assert_eq!(lhs, b as i128);
+        }
+        assert!(solution.values.iter().all(|&x| x >= 0));
+        let cost: i128 = problem
+            .costs
+            .iter()
+            .zip(&solution.values)
+            .map(|(&c, &x)| c as i128 * x)
+            .sum();
+        assert_eq!(cost, solution.objective);
+    }
+
+    #[test]
+    fn test_day10_machines() {
+        let mut objectives = Vec::new();
+        for line in parse_manual(TEST_INPUT).unwrap() {
+            let mut matrix = vec![vec![0; line.buttons.len()]; line.joltage.len()];
+            for (button, wiring) in line.buttons.iter().enumerate() {
+                for &counter in wiring {
+                    matrix[counter][button] = 1;
+                }
+            }
+            let rhs = line.joltage.iter().map(|&j| j as i64).collect();
+
+            let problem = Problem::new(line.buttons.len(), matrix, rhs);
+            let solution = problem.solve().unwrap();
+            check(&problem, &solution);
+            objectives.push(solution.objective);
+        }
+
+        assert_eq!(objectives, vec![10, 12, 11]);
+    }
+
+    #[test]
+    fn test_infeasibility_certificates() {
+        // x + y = 1 and 2x + 2y = 3 have no rational solution either
+        let problem = Problem::new(2, vec![vec![1, 1], vec![2, 2]], vec![1, 3]);
+        let Err(IlpError::Infeasible(Certificate::Inconsistent { multipliers })) = problem.solve()
+        else {
+            panic!("expected an inconsistency");
+        };
+        let (combined, rhs) = problem.combine(&multipliers);
+        assert!(combined.iter().all(|&a| a == 0));
+        assert_ne!(rhs, 0);
+
+        // 2x + 2y = 3 has rational solutions, but no integer ones
+        let problem = Problem::new(2, vec![vec![2, 2]], vec![3]);
+        assert_eq!(
+            problem.solve(),
+            Err(IlpError::Infeasible(Certificate::Exhausted {
+                free: vec![(1, 1)]
+            }))
+        );
+
+        // x - y = 1 with nothing bounding y
+        let problem = Problem::new(2, vec![vec![1, -1]], vec![1]);
+        assert_eq!(problem.solve(), Err(IlpError::Unbounded { variable: 1 }));
+        let solution = problem
+            .with_upper_bounds(vec![None, Some(5)])
+            .solve()
+            .unwrap();
+        assert_eq!(solution.values, vec![1, 0]);
+    }
+
+    #[test]
+    fn test_costs_and_bounds() {
+        // x + y + z = 6 and x = z, only the first row bounds anything
+        let problem = Problem::new(3, vec![vec![1, 1, 1], vec![1, 0, -1]], vec![6, 0]);
+        assert_eq!(problem.clone().solve().unwrap().objective, 6);
+
+        let solution = problem.clone().with_costs(vec![1, 5, 1]).solve().unwrap();
+        assert_eq!((solution.values, solution.objective), (vec![3, 0, 3], 6));
+
+        let solution = problem.clone().with_costs(vec![0, -1, 0]).solve().unwrap();
+        assert_eq!((solution.values, solution.objective), (vec![0, 6, 0], -6));
+
+        let solution = problem
+            .with_costs(vec![0, -1, 0])
+            .with_upper_bounds(vec![None, Some(3), None])
+            .solve()
+            .unwrap();
+        assert!(solution.values[1] == 2 && solution.objective == -2);
+
+        // a variable outside every row is 0, unless raising it pays off forever
+        let problem = Problem::new(2, vec![vec![1, 0]], vec![4]);
+        assert_eq!(problem.clone().solve().unwrap().values, vec![4, 0]);
+        assert_eq!(
+            problem.with_costs(vec![1, -1]).solve(),
+            Err(IlpError::Unbounded { variable: 1 })
+        );
+
+        // without any rows the variables are still there, and best left at 0
+        let problem = Problem::new(2, Vec::new(), Vec::new());
+        assert_eq!(problem.solve().unwrap().values, vec![0, 0]);
+    }
+
+    #[test]
+    fn test_matches_brute_force() {
+        let mut rng = Lcg::new(23);
+
+        for _ in 0..300 {
+            let (rows, variables) = (1 + rng.index(3), 1 + rng.index(4));
+            let matrix: Vec<Vec<i64>> = (0..rows)
+                .map(|_| (0..variables).map(|_| rng.below(5) as i64 - 1).collect())
+                .collect();
+            let rhs: Vec<i64> = (0..rows).map(|_| rng.below(9) as i64 - 2).collect();
+            let costs: Vec<i64> = (0..variables).map(|_| rng.below(5) as i64 - 1).collect();
+            let problem = Problem::new(variables, matrix, rhs)
+                .with_costs(costs)
+                .with_upper_bounds(vec![Some(5); variables]);
+
+            // every point of the box, best cost first found
+            let mut best: Option<i128> = None;
+            let mut values = vec![0i128; variables];
+            loop {
+                let solution = Solution {
+                    objective: problem
+                        .costs
+                        .iter()
+                        .zip(&values)
+                        .map(|(&c, &x)| c as i128 * x)
+                        .sum(),
+                    values: values.clone(),
+                };
+                let feasible = problem.matrix.iter().zip(&problem.rhs).all(|(row, &b)| {
+                    row.iter()
+                        .zip(&values)
+                        .map(|(&a, &x)| a as i128 * x)
+                        .sum::<i128>()
+                        == b as i128
+                });
+                if feasible && best.is_none_or(|best| solution.objective < best) {
+                    best = Some(solution.objective);
+                }
+
+                let Some(idx) = values.iter().position(|&x| x < 5) else {
+                    break;
+                };
+                values[..idx].fill(0);
+                values[idx] += 1;
+            }
+
+            match problem.solve() {
+                Ok(solution) => {
+                    check(&problem, &solution);
+                    assert!(solution.values.iter().all(|&x| x <= 5));
+                    assert_eq!(Some(solution.objective), best, "{problem:?}");
+                }
+                Err(IlpError::Infeasible(Certificate::Inconsistent { multipliers })) => {
+                    assert_eq!(best, None, "{problem:?}");
+                    let (combined, rhs) = problem.combine(&multipliers);
+                    assert!(combined.iter().all(|&a| a == 0) && rhs != 0);
+                }
+                Err(err) => assert_eq!(best, None, "{problem:?} {err}"),
+            }
+        }
+    }
+}
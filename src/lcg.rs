@@ -0,0 +1,33 @@
+/*
+    lcg:
+
+    A tiny deterministic pseudo random generator (a 64-bit linear congruential generator with
+    Knuth's MMIX constants), for generated puzzle inputs and the randomized tests that compare a
+    solver with brute force. The same seed always gives the same sequence, so failures reproduce.
+    Nothing here is fit for real randomness.
+*/
+
+#[derive(Clone, Debug)]
+pub struct Lcg {
+    state: u64,
+}
+
+impl Lcg {
+    pub fn new(seed: u64) -> Self {
+        Lcg { state: seed }
+    }
+
+    /// Next value in `0..bound`, taken from the high bits (the low bits of an LCG cycle quickly).
+    pub fn below(&mut self, bound: u64) -> u64 {
+        self.state = self
+            .state
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        (self.state >> 33) % bound
+    }
+
+    /// `below` for lengths and indices.
+    pub fn index(&mut self, bound: usize) -> usize {
+        self.below(bound as u64) as usize
+    }
+}
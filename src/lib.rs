@@ -1,8 +1,16 @@
+pub mod compressed_grid;
+pub mod day06;
+pub mod day07;
 pub mod day08;
 pub mod day09;
 pub mod day10;
 pub mod day11;
 pub mod day12;
+pub mod geometry;
+pub mod ilp;
+pub mod lcg;
+pub mod sections;
+pub mod unionfind;
 
 #[macro_export]
 macro_rules! time_it {
@@ -0,0 +1,188 @@
+/*
+    sections:
+
+    Several puzzles (day05 ranges vs ingredients, day12 shapes vs regions) have inputs made of
+    blocks separated by blank lines. This splits such inputs on one or more blank lines,
+    whatever the line endings, and keeps the original line numbers around so parse errors can
+    point at the section and line that caused them.
+*/
+use std::fmt;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SectionError {
+    pub section: String,
+    pub line: usize, // 1-based line in the whole input, 0 when there is no line to point at
+    pub message: String,
+}
+
+impl fmt::Display for SectionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.line == 0 {
+            write!(f, "section `{}`: {}", self.section, self.message)
+        } else {
+            write!(
+                f,
+                "section `{}`, line {}: {}",
+                self.section, self.line, self.message
+            )
+        }
+    }
+}
+
+impl std::error::Error for SectionError {}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Section<'a> {
+    pub name: String,
+    lines: Vec<(usize, &'a str)>, // (1-based line number, content without line ending)
+}
+
+impl<'a> Section<'a> {
+    /// Line number of the first line of the section.
+    pub fn first_line(&self) -> usize {
+        self.lines[0].0
+    }
+
+    /// Non-blank lines paired with their 1-based line number in the whole input.
+    pub fn lines(&self) -> impl Iterator<Item = (usize, &'a str)> + '_ {
+        self.lines.iter().copied()
+    }
+
+    pub fn len(&self) -> usize {
+        self.lines.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lines.is_empty()
+    }
+
+    /// Builds an error pointing at `line` inside this section.
+    pub fn error(&self, line: usize, message: impl Into<String>) -> SectionError {
+        SectionError {
+            section: self.name.clone(),
+            line,
+            message: message.into(),
+        }
+    }
+
+    /// Parses every line with `parse`, turning its error into one pointing at the offending line.
+    pub fn parse_lines<T, E: fmt::Display>(
+        &self,
+        mut parse: impl FnMut(&'a str) -> Result<T, E>,
+    ) -> Result<Vec<T>, SectionError> {
+        self.lines()
+            .map(|(line_no, line)| {
+                parse(line.trim()).map_err(|err| self.error(line_no, format!("{line:?}: {err}")))
+            })
+            .collect()
+    }
+}
+
+/// Splits `input` into blocks separated by one or more blank (or whitespace-only) lines.
+///
+/// `\r\n` endings are handled, as are leading and trailing blank lines. Sections are named
+/// `section 1`, `section 2`, ... until renamed.
+pub fn split_sections(input: &str) -> Vec<Section<'_>> {
+    let mut sections = Vec::new();
+    let mut current = Vec::new();
+
+    for (idx, line) in input.lines().enumerate() {
+        let line = line.trim_end_matches('\r');
+
+        if line.trim().is_empty() {
+            if !current.is_empty() {
+                sections.push(std::mem::take(&mut current));
+            }
+        } else {
+            current.push((idx + 1, line));
+        }
+    }
+
+    if !current.is_empty() {
+        sections.push(current);
+    }
+
+    sections
+        .into_iter()
+        .enumerate()
+        .map(|(idx, lines)| Section {
+            name: format!("section {}", idx + 1),
+            lines,
+        })
+        .collect()
+}
+
+/// Splits `input` and requires exactly one section per entry of `names`, in that order.
+pub fn parse_sections<'a>(
+    input: &'a str,
+    names: &[&str],
+) -> Result<Vec<Section<'a>>, SectionError> {
+    let mut sections = split_sections(input);
+
+    if let Some(missing) = names.get(sections.len()) {
+        return Err(SectionError {
+            section: missing.to_string(),
+            line: 0,
+            message: format!(
+                "missing section, expected {} but found {}",
+                names.len(),
+                sections.len()
+            ),
+        });
+    }
+
+    if let Some(extra) = sections.get(names.len()) {
+        return Err(extra.error(
+            extra.first_line(),
+            format!(
+                "unexpected section, expected only {}: {}",
+                names.len(),
+                names.join(", ")
+            ),
+        ));
+    }
+
+    for (section, name) in sections.iter_mut().zip(names) {
+        section.name = name.to_string();
+    }
+
+    Ok(sections)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_tolerates_crlf_and_repeated_blank_lines() {
+        let input = "\r\n3-5\r\n10-14\r\n\r\n \r\n\r\n1\r\n5\r\n\r\n";
+
+        let sections = parse_sections(input, &["ranges", "ingredients"]).unwrap();
+
+        assert_eq!(sections[0].name, "ranges");
+        assert_eq!(
+            sections[0].lines().collect::<Vec<_>>(),
+            vec![(2, "3-5"), (3, "10-14")]
+        );
+        assert_eq!(
+            sections[1].lines().collect::<Vec<_>>(),
+            vec![(7, "1"), (8, "5")]
+        );
+    }
+
+    #[test]
+    fn test_errors_point_at_section_and_line() {
+        let missing = parse_sections("3-5\n10-14\n", &["ranges", "ingredients"]).unwrap_err();
+        assert_eq!(missing.section, "ingredients");
+
+        let extra = parse_sections("1\n\n2\n\n3\n", &["a", "b"]).unwrap_err();
+        assert_eq!((extra.section.as_str(), extra.line), ("section 3", 5));
+
+        let sections = parse_sections("1\n\n2\nx\n", &["a", "b"]).unwrap();
+        let err = sections[1].parse_lines(str::parse::<u32>).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "section `b`, line 4: \"x\": invalid digit found in string"
+        );
+    }
+}
@@ -0,0 +1,239 @@
+/*
+    unionfind:
+
+    Disjoint sets over `0..n` with union by size, so every root knows how big its component is,
+    the number of components and the size of the largest one are kept up to date as sets merge.
+
+    `new` compresses paths on every `find`. `with_rollback` records every union instead, so it can
+    be undone (one at a time or back to a checkpoint); compressed paths could not be restored, so
+    it relies on union by size alone to keep trees shallow (O(log n) finds).
+*/
+
+#[derive(Clone, Debug)]
+pub struct UnionFind {
+    parent: Vec<usize>,
+    size: Vec<usize>, // only meaningful for roots
+    components: usize,
+    largest: usize,
+    history: Option<Vec<Union>>, // `Some` when unions can be undone
+}
+
+/// What a union changed, enough to put it back.
+#[derive(Clone, Copy, Debug)]
+struct Union {
+    child: usize,
+    root: usize,
+    largest: usize, // largest component size before the union
+}
+
+impl UnionFind {
+    pub fn new(n: usize) -> Self {
+        UnionFind {
+            parent: (0..n).collect(),
+            size: vec![1; n],
+            components: n,
+            largest: n.min(1),
+            history: None,
+        }
+    }
+
+    /// Like `new`, but every union can later be undone.
+    pub fn with_rollback(n: usize) -> Self {
+        UnionFind {
+            history: Some(Vec::new()),
+            ..UnionFind::new(n)
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.parent.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.parent.is_empty()
+    }
+
+    /// Representative of the component holding `v`.
+    pub fn find(&mut self, v: usize) -> usize {
+        let mut root = v;
+        while self.parent[root] != root {
+            root = self.parent[root];
+        }
+
+        if self.history.is_none() {
+            let mut cur = v;
+            while self.parent[cur] != root {
+                cur = std::mem::replace(&mut self.parent[cur], root);
+            }
+        }
+
+        root
+    }
+
+    pub fn same(&mut self, a: usize, b: usize) -> bool {
+        self.find(a) == self.find(b)
+    }
+
+    /// Merges the components of `a` and `b`; false when they already were one.
+    pub fn union(&mut self, a: usize, b: usize) -> bool {
+        let (mut root, mut child) = (self.find(a), self.find(b));
+        if root == child {
+            return false;
+        }
+
+        if self.size[root] < self.size[child] {
+            std::mem::swap(&mut root, &mut child);
+        }
+
+        if let Some(history) = &mut self.history {
+            history.push(Union {
+                child,
+                root,
+                largest: self.largest,
+            });
+        }
+
+        self.parent[child] = root;
+        self.size[root] += self.size[child];
+        self.components -= 1;
+        self.largest = self.largest.max(self.size[root]);
+
+        true
+    }
+
+    /// Size of the component holding `v`.
+    pub fn size_of(&mut self, v: usize) -> usize {
+        let root = self.find(v);
+        self.size[root]
+    }
+
+    pub fn component_count(&self) -> usize {
+        self.components
+    }
+
+    /// Size of the largest component, 0 when there are no elements.
+    pub fn largest_size(&self) -> usize {
+        self.largest
+    }
+
+    /// Sizes of every component, in the order of their representatives.
+    pub fn component_sizes(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..self.len())
+            .filter(|&v| self.parent[v] == v)
+            .map(|root| self.size[root])
+    }
+
+    /// Members of every component, each sorted, components ordered by their smallest member.
+    pub fn components(&mut self) -> impl Iterator<Item = Vec<usize>> {
+        let mut slot = vec![usize::MAX; self.len()];
+        let mut components: Vec<Vec<usize>> = Vec::with_capacity(self.components);
+
+        for v in 0..self.len() {
+            let root = self.find(v);
+            if slot[root] == usize::MAX {
+                slot[root] = components.len();
+                components.push(Vec::with_capacity(self.size[root]));
+            }
+            components[slot[root]].push(v);
+        }
+
+        components.into_iter()
+    }
+
+    /// Number of unions that can be undone, usable as a checkpoint for `rollback`.
+    ///
+    /// Panics unless created by `with_rollback`.
+    pub fn checkpoint(&self) -> usize {
+        self.history().len()
+    }
+
+    /// Undoes the last successful union; false when there is nothing left to undo.
+    ///
+    /// Panics unless created by `with_rollback`.
+    pub fn undo(&mut self) -> bool {
+        let Some(union) = self.history_mut().pop() else {
+            return false;
+        };
+
+        self.parent[union.child] = union.child;
+        self.size[union.root] -= self.size[union.child];
+        self.components += 1;
+        self.largest = union.largest;
+
+        true
+    }
+
+    /// Undoes unions until only the first `checkpoint` remain.
+    pub fn rollback(&mut self, checkpoint: usize) {
+        while self.checkpoint() > checkpoint {
+            self.undo();
+        }
+    }
+
+    fn history(&self) -> &Vec<Union> {
+        self.history
+            .as_ref()
+            .expect("union find was not created with rollback")
+    }
+
+    fn history_mut(&mut self) -> &mut Vec<Union> {
+        self.history
+            .as_mut()
+            .expect("union find was not created with rollback")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sizes_and_components() {
+        let mut uf = UnionFind::new(6);
+
+        assert!(uf.union(0, 1));
+        assert!(uf.union(4, 1));
+        assert!(uf.union(2, 5));
+        assert!(!uf.union(0, 4));
+
+        assert!(uf.same(0, 4));
+        assert!(!uf.same(0, 2));
+        assert_eq!(uf.size_of(4), 3);
+        assert_eq!(uf.component_count(), 3);
+        assert_eq!(uf.largest_size(), 3);
+
+        let mut sizes: Vec<_> = uf.component_sizes().collect();
+        sizes.sort_unstable();
+        assert_eq!(sizes, vec![1, 2, 3]);
+        assert_eq!(
+            uf.components().collect::<Vec<_>>(),
+            vec![vec![0, 1, 4], vec![2, 5], vec![3]]
+        );
+    }
+
+    #[test]
+    fn test_rollback() {
+        let mut uf = UnionFind::with_rollback(5);
+
+        uf.union(0, 1);
+        let checkpoint = uf.checkpoint();
+        uf.union(2, 3);
+        uf.union(1, 3);
+        uf.union(0, 2); // already joined, nothing to undo
+        assert_eq!((uf.component_count(), uf.largest_size()), (2, 4));
+
+        assert!(uf.undo());
+        assert_eq!((uf.component_count(), uf.largest_size()), (3, 2));
+        assert!(!uf.same(0, 3));
+        assert!(uf.same(2, 3));
+
+        uf.rollback(checkpoint);
+        assert_eq!(uf.component_count(), 4);
+        assert_eq!(uf.size_of(1), 2);
+        assert_eq!(uf.size_of(2), 1);
+
+        uf.rollback(0);
+        assert!(!uf.undo());
+        assert_eq!((uf.component_count(), uf.largest_size()), (5, 1));
+    }
+}